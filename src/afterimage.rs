@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::{animation::apply_visual_transforms, GameSet};
+
+#[cfg(test)]
+mod tests;
+
+// ghosts sit right behind the sprite they were taken from
+const GHOST_Z_OFFSET: f32 = -0.1;
+
+pub struct AfterimagePlugin;
+
+impl Plugin for AfterimagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            leave_afterimages
+                .in_set(GameSet::Render)
+                .after(apply_visual_transforms),
+        )
+        .add_system(fade_afterimages.in_set(GameSet::Render));
+    }
+}
+
+/// Leaves a ghost of the current frame behind the sprite every `interval` seconds while active,
+/// for dashes, rolls or fast falls. Ghosts fade from `tint` to nothing over `lifetime` seconds.
+#[derive(Component, Debug, Clone)]
+pub struct Afterimage {
+    pub active: bool,
+    pub interval: f32,
+    pub lifetime: f32,
+    pub tint: Color,
+    elapsed: f32,
+}
+
+impl Afterimage {
+    pub fn new(interval: f32, lifetime: f32, tint: Color) -> Self {
+        Self {
+            active: true,
+            interval,
+            lifetime,
+            tint,
+            // the first ghost comes right away
+            elapsed: interval,
+        }
+    }
+
+    /// Whether a ghost is due after `delta` seconds.
+    pub fn tick(&mut self, delta: f32) -> bool {
+        if !self.active {
+            self.elapsed = self.interval;
+            return false;
+        }
+        self.elapsed += delta;
+        if self.elapsed < self.interval {
+            return false;
+        }
+        // a long frame leaves a single ghost, not a pile of them
+        self.elapsed = (self.elapsed - self.interval) % self.interval;
+        true
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Ghost {
+    tint: Color,
+    timer: Timer,
+}
+
+impl Ghost {
+    fn new(tint: Color, lifetime: f32) -> Self {
+        Self {
+            tint,
+            timer: Timer::from_seconds(lifetime, TimerMode::Once),
+        }
+    }
+
+    /// Color of the ghost, fading out with its age.
+    pub fn color(&self) -> Color {
+        self.tint.with_a(self.tint.a() * self.timer.percent_left())
+    }
+}
+
+fn leave_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut trail_query: Query<(
+        &mut Afterimage,
+        &GlobalTransform,
+        &TextureAtlasSprite,
+        &Handle<TextureAtlas>,
+    )>,
+) {
+    for (mut afterimage, transform, sprite, atlas) in trail_query.iter_mut() {
+        if !afterimage.tick(time.delta_seconds()) {
+            continue;
+        }
+
+        // the sprite already faces the orientation of the character, see VisualTransform
+        let ghost = Ghost::new(afterimage.tint, afterimage.lifetime);
+        commands.spawn((
+            Name::new("Ghost"),
+            SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    index: sprite.index,
+                    color: ghost.color(),
+                    flip_x: sprite.flip_x,
+                    flip_y: sprite.flip_y,
+                    custom_size: sprite.custom_size,
+                    anchor: sprite.anchor.clone(),
+                },
+                texture_atlas: atlas.clone(),
+                transform: Transform::from_translation(
+                    transform.translation() + Vec3::Z * GHOST_Z_OFFSET,
+                ),
+                ..default()
+            },
+            ghost,
+        ));
+    }
+}
+
+fn fade_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut ghost_query: Query<(Entity, &mut Ghost, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut ghost, mut sprite) in ghost_query.iter_mut() {
+        ghost.timer.tick(time.delta());
+        if ghost.timer.finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite.color = ghost.color();
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{test_support::FRAME, GameSetsPlugin};
+
+use super::{Afterimage, AfterimagePlugin, Ghost};
+
+#[test]
+fn ghosts_are_left_every_interval_while_active() {
+    let mut afterimage = Afterimage::new(0.25, 1.0, Color::WHITE);
+    assert!(afterimage.tick(0.125));
+    assert!(!afterimage.tick(0.125));
+    assert!(afterimage.tick(0.125));
+
+    afterimage.active = false;
+    assert!(!afterimage.tick(1.0));
+    afterimage.active = true;
+    assert!(afterimage.tick(0.0));
+}
+
+#[test]
+fn ghosts_keep_the_frame_and_flip_and_fade() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(GameSetsPlugin)
+        .add_plugin(AfterimagePlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+    let character = app
+        .world
+        .spawn((
+            SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    index: 3,
+                    flip_x: true,
+                    ..default()
+                },
+                ..default()
+            },
+            Afterimage::new(10.0, 0.5, Color::rgba(1.0, 1.0, 1.0, 0.5)),
+        ))
+        .id();
+
+    app.update();
+    app.update();
+    let mut ghost_query = app.world.query::<(&Ghost, &TextureAtlasSprite)>();
+    let (ghost, sprite) = ghost_query.single(&app.world);
+    assert_eq!(sprite.index, 3);
+    assert!(sprite.flip_x);
+    assert!(ghost.color().a() < 0.5);
+
+    app.world.entity_mut(character).remove::<Afterimage>();
+    for _ in 0..40 {
+        app.update();
+    }
+    assert_eq!(ghost_query.iter(&app.world).count(), 0);
+}
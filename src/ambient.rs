@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+use bevy_ecs_tilemap::tiles::TileColor;
+
+use crate::{
+    sprite_effects::{apply_sprite_effects, SpriteEffects},
+    GameSet,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// How much the level tiles follow the ambient light.
+pub const TILE_AMBIENT_STRENGTH: f32 = 1.0;
+/// Characters follow it a bit less so they stay readable at night.
+pub const CHARACTER_AMBIENT_STRENGTH: f32 = 0.8;
+
+// a full day, in seconds
+const DEFAULT_DAY_DURATION: f32 = 240.0;
+const DEFAULT_TIME_OF_DAY: f32 = 0.4;
+
+pub struct AmbientPlugin;
+
+impl Plugin for AmbientPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AmbientLight>()
+            .add_system(advance_ambient_light.in_set(GameSet::BeforeUpdate))
+            .add_system(track_tiles.in_set(GameSet::Render))
+            .add_system(tint_tiles.in_set(GameSet::Render).after(track_tiles))
+            .add_system(tint_sprites.in_set(GameSet::Render))
+            .add_system(
+                tint_atlas_sprites
+                    .in_set(GameSet::Render)
+                    .before(apply_sprite_effects),
+            );
+    }
+}
+
+/// Ambient color at `time` of the day, between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientKeyframe {
+    pub time: f32,
+    pub color: Color,
+}
+
+impl AmbientKeyframe {
+    pub fn new(time: f32, color: Color) -> Self {
+        Self { time, color }
+    }
+}
+
+/// Time of day and the ambient color it gives, shared by everything with an [`AmbientTint`].
+///
+/// The time goes around every `day_duration` seconds. Scripted scenes can pause it, jump to a
+/// time of day or force a color with an override.
+#[derive(Resource, Debug, Clone)]
+pub struct AmbientLight {
+    pub day_duration: f32,
+    keyframes: Vec<AmbientKeyframe>,
+    time_of_day: f32,
+    paused: bool,
+    override_color: Option<Color>,
+}
+
+impl Default for AmbientLight {
+    fn default() -> Self {
+        let night = Color::rgb(0.3, 0.35, 0.6);
+        Self::new(
+            DEFAULT_DAY_DURATION,
+            vec![
+                AmbientKeyframe::new(0.0, night),
+                AmbientKeyframe::new(0.22, night),
+                AmbientKeyframe::new(0.3, Color::rgb(1.0, 0.75, 0.65)),
+                AmbientKeyframe::new(0.38, Color::WHITE),
+                AmbientKeyframe::new(0.68, Color::WHITE),
+                AmbientKeyframe::new(0.76, Color::rgb(1.0, 0.6, 0.45)),
+                AmbientKeyframe::new(0.84, night),
+            ],
+        )
+        .with_time_of_day(DEFAULT_TIME_OF_DAY)
+    }
+}
+
+impl AmbientLight {
+    pub fn new(day_duration: f32, mut keyframes: Vec<AmbientKeyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            day_duration,
+            keyframes,
+            time_of_day: 0.0,
+            paused: false,
+            override_color: None,
+        }
+    }
+
+    pub fn with_time_of_day(mut self, time_of_day: f32) -> Self {
+        self.set_time_of_day(time_of_day);
+        self
+    }
+
+    pub fn time_of_day(&self) -> f32 {
+        self.time_of_day
+    }
+
+    pub fn set_time_of_day(&mut self, time_of_day: f32) {
+        self.time_of_day = time_of_day.rem_euclid(1.0);
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Shows `color` whatever the time of day, until cleared. The cycle keeps going underneath
+    /// unless paused.
+    pub fn set_override(&mut self, color: Color) {
+        self.override_color = Some(color);
+    }
+
+    pub fn clear_override(&mut self) {
+        self.override_color = None;
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        if !self.paused && self.day_duration > 0.0 {
+            self.set_time_of_day(self.time_of_day + delta / self.day_duration);
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        if let Some(color) = self.override_color {
+            return color;
+        }
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return Color::WHITE;
+        };
+
+        // the day wraps around, the last keyframe blends into the first one of the next day
+        let next_index = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.time > self.time_of_day);
+        let (from, to) = match next_index {
+            Some(0) | None => (*last, *first),
+            Some(index) => (self.keyframes[index - 1], self.keyframes[index]),
+        };
+        let span = (to.time - from.time).rem_euclid(1.0);
+        let t = match span > 0.0 {
+            true => (self.time_of_day - from.time).rem_euclid(1.0) / span,
+            false => 0.0,
+        };
+
+        let [r, g, b, _] = from.color.as_rgba_f32();
+        let [to_r, to_g, to_b, _] = to.color.as_rgba_f32();
+        Color::rgb(r + (to_r - r) * t, g + (to_g - g) * t, b + (to_b - b) * t)
+    }
+}
+
+/// Follows the [`AmbientLight`] by `strength`: 0 ignores it, 1 takes it as it is and more pushes
+/// further, for distant layers. The ambient color multiplies `base`.
+#[derive(Component, Debug, Clone)]
+pub struct AmbientTint {
+    pub strength: f32,
+    pub base: Color,
+}
+
+impl AmbientTint {
+    pub fn new(strength: f32) -> Self {
+        Self {
+            strength,
+            base: Color::WHITE,
+        }
+    }
+
+    pub fn color(&self, ambient: Color) -> Color {
+        let [r, g, b, a] = self.base.as_rgba_f32();
+        let [ambient_r, ambient_g, ambient_b, _] = ambient.as_rgba_f32();
+        let channel =
+            |base: f32, ambient: f32| base * (1.0 + (ambient - 1.0) * self.strength).max(0.0);
+        Color::rgba(
+            channel(r, ambient_r),
+            channel(g, ambient_g),
+            channel(b, ambient_b),
+            a,
+        )
+    }
+}
+
+fn advance_ambient_light(time: Res<Time>, mut ambient_light: ResMut<AmbientLight>) {
+    // a paused light is left untouched, so nothing gets tinted again
+    if !ambient_light.is_paused() {
+        ambient_light.tick(time.delta_seconds());
+    }
+}
+
+// tiles come and go with the levels, they keep the color they were spawned with as a base
+fn track_tiles(
+    mut commands: Commands,
+    tile_query: Query<(Entity, &TileColor), (Added<TileColor>, Without<AmbientTint>)>,
+) {
+    for (entity, tile_color) in tile_query.iter() {
+        commands.entity(entity).insert(AmbientTint {
+            base: tile_color.0,
+            ..AmbientTint::new(TILE_AMBIENT_STRENGTH)
+        });
+    }
+}
+
+fn tint_tiles(
+    ambient_light: Res<AmbientLight>,
+    mut tile_query: Query<(Ref<AmbientTint>, &mut TileColor)>,
+) {
+    let ambient = ambient_light.color();
+    for (tint, mut tile_color) in tile_query.iter_mut() {
+        if ambient_light.is_changed() || tint.is_changed() {
+            tile_color.0 = tint.color(ambient);
+        }
+    }
+}
+
+fn tint_sprites(
+    ambient_light: Res<AmbientLight>,
+    mut sprite_query: Query<(Ref<AmbientTint>, &mut Sprite)>,
+) {
+    let ambient = ambient_light.color();
+    for (tint, mut sprite) in sprite_query.iter_mut() {
+        if ambient_light.is_changed() || tint.is_changed() {
+            sprite.color = tint.color(ambient);
+        }
+    }
+}
+
+// effects are drawn over the ambient color, which becomes the color they go back to
+fn tint_atlas_sprites(
+    ambient_light: Res<AmbientLight>,
+    mut sprite_query: Query<(
+        Ref<AmbientTint>,
+        &mut TextureAtlasSprite,
+        Option<&mut SpriteEffects>,
+    )>,
+) {
+    let ambient = ambient_light.color();
+    for (tint, mut sprite, effects) in sprite_query.iter_mut() {
+        if !ambient_light.is_changed() && !tint.is_changed() {
+            continue;
+        }
+        let color = tint.color(ambient);
+        match effects {
+            Some(mut effects) => {
+                effects.base_color = color;
+                if effects.is_empty() {
+                    sprite.color = color;
+                }
+            }
+            None => sprite.color = color,
+        }
+    }
+}
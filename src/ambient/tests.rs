@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use super::{AmbientKeyframe, AmbientLight, AmbientTint};
+
+fn light() -> AmbientLight {
+    AmbientLight::new(
+        4.0,
+        vec![
+            AmbientKeyframe::new(0.75, Color::rgb(0.0, 0.0, 1.0)),
+            AmbientKeyframe::new(0.25, Color::rgb(1.0, 1.0, 1.0)),
+        ],
+    )
+}
+
+#[test]
+fn the_color_blends_between_keyframes_across_midnight() {
+    let mut ambient_light = light().with_time_of_day(0.5);
+    assert_eq!(ambient_light.color(), Color::rgb(0.5, 0.5, 1.0));
+
+    ambient_light.set_time_of_day(0.75);
+    assert_eq!(ambient_light.color(), Color::rgb(0.0, 0.0, 1.0));
+
+    ambient_light.set_time_of_day(0.0);
+    assert_eq!(ambient_light.color(), Color::rgb(0.5, 0.5, 1.0));
+}
+
+#[test]
+fn the_day_goes_around_unless_paused() {
+    let mut ambient_light = light().with_time_of_day(0.75);
+    ambient_light.tick(1.0);
+    assert_eq!(ambient_light.time_of_day(), 0.0);
+
+    ambient_light.pause();
+    ambient_light.tick(1.0);
+    assert_eq!(ambient_light.time_of_day(), 0.0);
+}
+
+#[test]
+fn overrides_win_until_cleared() {
+    let mut ambient_light = light().with_time_of_day(0.25);
+    ambient_light.set_override(Color::RED);
+    assert_eq!(ambient_light.color(), Color::RED);
+
+    ambient_light.clear_override();
+    assert_eq!(ambient_light.color(), Color::WHITE);
+}
+
+#[test]
+fn distant_layers_shift_more() {
+    let ambient = Color::rgb(0.5, 0.5, 1.0);
+    assert_eq!(AmbientTint::new(0.0).color(ambient), Color::WHITE);
+    assert_eq!(AmbientTint::new(1.0).color(ambient), ambient);
+    assert_eq!(
+        AmbientTint::new(1.5).color(ambient),
+        Color::rgb(0.25, 0.25, 1.0)
+    );
+    assert_eq!(
+        AmbientTint::new(3.0).color(ambient),
+        Color::rgb(0.0, 0.0, 1.0)
+    );
+}
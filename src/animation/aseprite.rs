@@ -0,0 +1,276 @@
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use super::sheet::{AnimationSheet, Clip, ClipFrame, FrameBox, PlaybackMode};
+
+// a slice with this name places the sprite anchor on its pivot
+const PIVOT_SLICE: &str = "pivot";
+// slices with these names become the frame boxes, an empty key removes the box
+const HITBOX_SLICE: &str = "hitbox";
+const HURTBOX_SLICE: &str = "hurtbox";
+
+#[derive(Deserialize)]
+struct AsepriteRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSize {
+    w: f32,
+    h: f32,
+}
+
+#[derive(Deserialize)]
+struct AsepritePoint {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    frame: AsepriteRect,
+    duration: u32,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum AsepriteDirection {
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
+
+#[derive(Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    direction: AsepriteDirection,
+    // only written by aseprite when the tag does not loop forever
+    #[serde(default)]
+    repeat: Option<String>,
+    // user data of the tag, lists the frame events as `position:name` pairs
+    #[serde(default)]
+    data: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSliceKey {
+    frame: usize,
+    bounds: AsepriteRect,
+    #[serde(default)]
+    pivot: Option<AsepritePoint>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSlice {
+    name: String,
+    keys: Vec<AsepriteSliceKey>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsepriteMeta {
+    image: String,
+    size: AsepriteSize,
+    #[serde(default)]
+    frame_tags: Vec<AsepriteTag>,
+    #[serde(default)]
+    slices: Vec<AsepriteSlice>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSheet {
+    frames: Vec<AsepriteFrame>,
+    meta: AsepriteMeta,
+}
+
+impl AsepriteSlice {
+    // a key stays in effect until the next one
+    fn key_at(&self, frame: usize) -> Option<&AsepriteSliceKey> {
+        self.keys
+            .iter()
+            .filter(|key| key.frame <= frame)
+            .max_by_key(|key| key.frame)
+    }
+
+    // the box is placed relatively to the pivot, y pointing up like in the world
+    fn frame_box(&self, frame: usize, pivot: Vec2) -> Option<FrameBox> {
+        self.key_at(frame)
+            .filter(|key| key.bounds.w > 0. && key.bounds.h > 0.)
+            .map(|key| {
+                let half_size = Vec2::new(key.bounds.w, key.bounds.h) / 2.;
+                FrameBox {
+                    offset: Vec2::new(
+                        key.bounds.x + half_size.x - pivot.x,
+                        pivot.y - key.bounds.y - half_size.y,
+                    ),
+                    half_size,
+                }
+            })
+    }
+}
+
+impl AsepriteTag {
+    fn frames(&self) -> Vec<usize> {
+        match self.direction {
+            AsepriteDirection::Forward | AsepriteDirection::Pingpong => {
+                (self.from..=self.to).collect()
+            }
+            AsepriteDirection::Reverse | AsepriteDirection::PingpongReverse => {
+                (self.from..=self.to).rev().collect()
+            }
+        }
+    }
+
+    fn mode(&self) -> PlaybackMode {
+        match (self.direction, self.repeat.as_deref()) {
+            (_, Some("1")) => PlaybackMode::Once,
+            (AsepriteDirection::Pingpong | AsepriteDirection::PingpongReverse, _) => {
+                PlaybackMode::PingPong
+            }
+            _ => PlaybackMode::Loop,
+        }
+    }
+
+    // "1:footstep 4:footstep" sends footstep on the second and fifth frames of the tag
+    fn events(&self, position: usize) -> Vec<String> {
+        self.data
+            .iter()
+            .flat_map(|data| data.split_whitespace())
+            .filter_map(|event| event.split_once(':'))
+            .filter(|(event_position, _)| event_position.parse() == Ok(position))
+            .map(|(_, name)| name.to_string())
+            .collect()
+    }
+}
+
+/// Reads sheets exported by aseprite with the `Array` frame layout: frames become the texture
+/// atlas, tags become clips named after them and the `pivot` slice becomes the sprite anchor.
+/// Frame events are read from the user data of the tags, frame boxes from the `hitbox` and
+/// `hurtbox` slices.
+#[derive(Default)]
+pub struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let aseprite: AsepriteSheet = serde_json::from_slice(bytes)?;
+
+            // the image is referenced relatively to the sheet
+            let image_path = AssetPath::new(
+                load_context
+                    .path()
+                    .parent()
+                    .unwrap_or(std::path::Path::new(""))
+                    .join(&aseprite.meta.image),
+                None,
+            );
+            let mut texture_atlas = TextureAtlas::new_empty(
+                load_context.get_handle(image_path.clone()),
+                Vec2::new(aseprite.meta.size.w, aseprite.meta.size.h),
+            );
+            for frame in aseprite.frames.iter() {
+                texture_atlas.add_texture(Rect::new(
+                    frame.frame.x,
+                    frame.frame.y,
+                    frame.frame.x + frame.frame.w,
+                    frame.frame.y + frame.frame.h,
+                ));
+            }
+            let atlas = load_context.set_labeled_asset("atlas", LoadedAsset::new(texture_atlas));
+
+            let find_slice =
+                |name: &str| aseprite.meta.slices.iter().find(|slice| slice.name == name);
+            let pivot_slice = find_slice(PIVOT_SLICE);
+            let hitbox_slice = find_slice(HITBOX_SLICE);
+            let hurtbox_slice = find_slice(HURTBOX_SLICE);
+            // position of the pivot inside a frame, its center when there is none
+            let pivot_at = |index: usize, frame: &AsepriteFrame| {
+                pivot_slice
+                    .and_then(|slice| slice.key_at(index))
+                    .and_then(|key| {
+                        key.pivot
+                            .as_ref()
+                            .map(|pivot| Vec2::new(key.bounds.x + pivot.x, key.bounds.y + pivot.y))
+                    })
+                    .unwrap_or(Vec2::new(frame.frame.w, frame.frame.h) / 2.)
+            };
+
+            let mut clips = HashMap::new();
+            for tag in aseprite.meta.frame_tags.iter() {
+                let Some(first_frame) = aseprite.frames.get(tag.from) else {
+                    return Err(bevy::asset::Error::msg(format!(
+                        "tag {} starts after the last frame",
+                        tag.name
+                    )));
+                };
+
+                let anchor = pivot_slice
+                    .and_then(|slice| slice.key_at(tag.from))
+                    .and_then(|key| {
+                        key.pivot.as_ref().map(|pivot| {
+                            Vec2::new(
+                                (key.bounds.x + pivot.x) / first_frame.frame.w - 0.5,
+                                0.5 - (key.bounds.y + pivot.y) / first_frame.frame.h,
+                            )
+                        })
+                    });
+
+                clips.insert(
+                    tag.name.clone(),
+                    Clip {
+                        atlas: atlas.clone(),
+                        frames: tag
+                            .frames()
+                            .into_iter()
+                            .enumerate()
+                            .filter_map(|(position, index)| {
+                                aseprite.frames.get(index).map(|frame| {
+                                    let pivot = pivot_at(index, frame);
+                                    ClipFrame {
+                                        index,
+                                        duration: frame.duration as f32 / 1000.,
+                                        events: tag.events(position),
+                                        hitbox: hitbox_slice
+                                            .and_then(|slice| slice.frame_box(index, pivot)),
+                                        hurtbox: hurtbox_slice
+                                            .and_then(|slice| slice.frame_box(index, pivot)),
+                                    }
+                                })
+                            })
+                            .collect(),
+                        mode: tag.mode(),
+                        anchor,
+                    },
+                );
+            }
+
+            load_context.set_default_asset(
+                LoadedAsset::new(AnimationSheet {
+                    clips,
+                    transitions: Vec::new(),
+                    packed: false,
+                })
+                .with_dependency(image_path),
+            );
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
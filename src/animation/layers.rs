@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use crate::GameSet;
+
+use super::visual::apply_visual_transforms;
+
+/// Keeps the layer sprites of [`SpriteLayers`] in sync with the sprite they sit on.
+pub struct SpriteLayersPlugin;
+
+impl Plugin for SpriteLayersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(update_sprite_layers.in_set(GameSet::AfterUpdate))
+            .add_system(
+                sync_sprite_layers
+                    .in_set(GameSet::Render)
+                    .after(apply_visual_transforms),
+            );
+    }
+}
+
+/// Sprite drawn over the animated sprite of its parent, with the same frame index, anchor, size and
+/// color. Its atlas has to be laid out like the base one.
+#[derive(Debug, Clone)]
+pub struct SpriteLayer {
+    pub name: &'static str,
+    pub atlas: Handle<TextureAtlas>,
+    /// Added to the z of the base sprite, negative values go behind it.
+    pub z_offset: f32,
+    pub visible: bool,
+}
+
+impl SpriteLayer {
+    pub fn new(name: &'static str, atlas: Handle<TextureAtlas>, z_offset: f32) -> Self {
+        Self {
+            name,
+            atlas,
+            z_offset,
+            visible: true,
+        }
+    }
+}
+
+/// Equipment, capes or weapon trails drawn on top of an animated sprite. Every layer is a child
+/// sprite that copies the flip of its parent.
+#[derive(Component, Debug, Clone, Default)]
+pub struct SpriteLayers {
+    layers: Vec<SpriteLayer>,
+}
+
+impl SpriteLayers {
+    pub fn with_layer(mut self, layer: SpriteLayer) -> Self {
+        self.layers.retain(|existing| existing.name != layer.name);
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut SpriteLayer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    pub fn set_visible(&mut self, name: &str, visible: bool) {
+        if let Some(layer) = self.get_mut(name) {
+            layer.visible = visible;
+        }
+    }
+}
+
+#[derive(Component)]
+struct LayerSprite(&'static str);
+
+fn update_sprite_layers(
+    mut commands: Commands,
+    layers_query: Query<(Entity, &SpriteLayers, Option<&Children>), Changed<SpriteLayers>>,
+    mut layer_query: Query<(
+        &LayerSprite,
+        &mut Handle<TextureAtlas>,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    for (entity, sprite_layers, children) in layers_query.iter() {
+        let mut existing = Vec::new();
+        for &child in children.into_iter().flatten() {
+            let Ok((layer_sprite, mut atlas, mut transform, mut visibility)) =
+                layer_query.get_mut(child)
+            else {
+                continue;
+            };
+            match sprite_layers
+                .layers
+                .iter()
+                .find(|layer| layer.name == layer_sprite.0)
+            {
+                Some(layer) => {
+                    existing.push(layer.name);
+                    if *atlas != layer.atlas {
+                        *atlas = layer.atlas.clone();
+                    }
+                    transform.translation.z = layer.z_offset;
+                    visibility.set_if_neq(match layer.visible {
+                        true => Visibility::Inherited,
+                        false => Visibility::Hidden,
+                    });
+                }
+                None => commands.entity(child).despawn_recursive(),
+            }
+        }
+
+        for layer in sprite_layers.layers.iter() {
+            if existing.contains(&layer.name) {
+                continue;
+            }
+            let layer_entity = commands
+                .spawn((
+                    Name::new(format!("{} layer", layer.name)),
+                    LayerSprite(layer.name),
+                    SpriteSheetBundle {
+                        texture_atlas: layer.atlas.clone(),
+                        transform: Transform::from_xyz(0.0, 0.0, layer.z_offset),
+                        visibility: match layer.visible {
+                            true => Visibility::Inherited,
+                            false => Visibility::Hidden,
+                        },
+                        ..default()
+                    },
+                ))
+                .id();
+            commands.entity(entity).add_child(layer_entity);
+        }
+    }
+}
+
+fn sync_sprite_layers(
+    base_query: Query<(&TextureAtlasSprite, &Children), With<SpriteLayers>>,
+    mut layer_query: Query<&mut TextureAtlasSprite, (With<LayerSprite>, Without<SpriteLayers>)>,
+) {
+    for (base_sprite, children) in base_query.iter() {
+        for &child in children.iter() {
+            let Ok(mut layer_sprite) = layer_query.get_mut(child) else {
+                continue;
+            };
+            layer_sprite.index = base_sprite.index;
+            layer_sprite.color = base_sprite.color;
+            layer_sprite.flip_x = base_sprite.flip_x;
+            layer_sprite.flip_y = base_sprite.flip_y;
+            layer_sprite.custom_size = base_sprite.custom_size;
+            layer_sprite.anchor = base_sprite.anchor.clone();
+        }
+    }
+}
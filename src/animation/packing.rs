@@ -0,0 +1,95 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{
+    plugin::{AnimationHandles, AnimationStateComponent},
+    sheet::AnimationSheet,
+};
+
+/// Combines the strips used by a sheet into a single atlas once their images have loaded, and
+/// points every clip at it with its frame indices shifted accordingly.
+///
+/// The sheet is modified in place, so animations pick the packed atlas up like any other reload,
+/// keeping their position in the clip. A sheet edited on disk comes back with its strips and is
+/// packed again.
+pub fn pack_animation_sheet<T: AnimationStateComponent>(
+    animation_handles: Res<AnimationHandles<T>>,
+    mut animation_sheets: ResMut<Assets<AnimationSheet>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(sheet) = animation_sheets.get(&animation_handles.sheet) else {
+        return;
+    };
+    if sheet.packed {
+        return;
+    }
+    let mut strips: Vec<Handle<TextureAtlas>> = Vec::new();
+    for clip in sheet.clips.values() {
+        if !strips.contains(&clip.atlas) {
+            strips.push(clip.atlas.clone());
+        }
+    }
+    // a sheet drawn on a single image is already as packed as it gets
+    if strips.len() < 2 {
+        return;
+    }
+
+    let mut builder = TextureAtlasBuilder::default();
+    for strip in strips.iter() {
+        let Some(image) = texture_atlases
+            .get(strip)
+            .and_then(|strip_atlas| images.get(&strip_atlas.texture))
+        else {
+            return;
+        };
+        builder.add_texture(texture_atlases.get(strip).unwrap().texture.clone(), image);
+    }
+    let strips_atlas = match builder.finish(&mut images) {
+        Ok(strips_atlas) => strips_atlas,
+        Err(error) => {
+            error!("cannot pack the animation strips: {:?}", error);
+            // the strips keep working on their own, there is no point trying every frame
+            animation_sheets
+                .get_mut(&animation_handles.sheet)
+                .unwrap()
+                .packed = true;
+            return;
+        }
+    };
+
+    // frames keep their rectangle inside the strip, moved to where the strip was packed
+    let mut textures = Vec::new();
+    let mut offsets = HashMap::new();
+    for strip in strips.iter() {
+        let strip_atlas = texture_atlases.get(strip).unwrap();
+        let Some(placement) = strips_atlas
+            .get_texture_index(&strip_atlas.texture)
+            .map(|index| strips_atlas.textures[index])
+        else {
+            continue;
+        };
+        offsets.insert(strip.clone(), textures.len());
+        textures.extend(strip_atlas.textures.iter().map(|rect| Rect {
+            min: rect.min + placement.min,
+            max: rect.max + placement.min,
+        }));
+    }
+    let packed_atlas = texture_atlases.add(TextureAtlas {
+        texture: strips_atlas.texture,
+        size: strips_atlas.size,
+        textures,
+        texture_handles: None,
+    });
+
+    let sheet = animation_sheets.get_mut(&animation_handles.sheet).unwrap();
+    sheet.packed = true;
+    for clip in sheet.clips.values_mut() {
+        let Some(&offset) = offsets.get(&clip.atlas) else {
+            continue;
+        };
+        clip.atlas = packed_atlas.clone();
+        for frame in clip.frames.iter_mut() {
+            frame.index += offset;
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use strum::IntoEnumIterator;
+
+use crate::GameSet;
+
+use super::{
+    layers::SpriteLayersPlugin,
+    packing::pack_animation_sheet,
+    sheet::{
+        AnimationEvent, AnimationSheet, AnimationSheetPlugin, AnimationTimeScale, Clip, ClipFrame,
+        PlaybackMode,
+    },
+    validation::validate_animation_sheet,
+    visual::VisualTransformPlugin,
+};
+
+pub trait AnimationStateComponent =
+    AnimationState + IntoEnumIterator + Component + Send + Sync + 'static;
+
+pub struct AnimationPlugin<T: AnimationStateComponent> {
+    sheet_path: &'static str,
+    phantom: PhantomData<T>,
+}
+
+impl<T: AnimationStateComponent> AnimationPlugin<T> {
+    pub fn new(sheet_path: &'static str) -> Self {
+        Self {
+            sheet_path,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: AnimationStateComponent> Plugin for AnimationPlugin<T> {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AnimationSheetPlugin>() {
+            app.add_plugin(AnimationSheetPlugin);
+        }
+        if !app.is_plugin_added::<SpriteLayersPlugin>() {
+            app.add_plugin(SpriteLayersPlugin);
+        }
+        if !app.is_plugin_added::<VisualTransformPlugin>() {
+            app.add_plugin(VisualTransformPlugin);
+        }
+
+        app.insert_resource(AnimationHandles::<T>::new(self.sheet_path))
+            .add_startup_system(load_animation_sheet::<T>)
+            // the strips are checked as declared, before they are packed
+            .add_system(validate_animation_sheet::<T>.in_set(GameSet::BeforeUpdate))
+            .add_system(
+                pack_animation_sheet::<T>
+                    .in_set(GameSet::BeforeUpdate)
+                    .after(validate_animation_sheet::<T>),
+            )
+            .add_system(update_animation::<T>.in_set(GameSet::AfterUpdate));
+    }
+}
+
+#[derive(Bundle)]
+pub struct AnimationBundle<T: AnimationState + Component> {
+    animation: Animation,
+    state: T,
+}
+
+impl<T: AnimationState + Component> AnimationBundle<T> {
+    pub fn new(initial_state: T) -> Self {
+        Self {
+            animation: Animation::default(),
+            state: initial_state,
+        }
+    }
+}
+
+pub trait AnimationState {
+    fn clip_name(&self) -> &'static str;
+}
+
+#[derive(Resource)]
+pub struct AnimationHandles<T> {
+    pub sheet: Handle<AnimationSheet>,
+    sheet_path: &'static str,
+    phantom: PhantomData<T>,
+}
+
+impl<T> AnimationHandles<T> {
+    pub(super) fn new(sheet_path: &'static str) -> Self {
+        Self {
+            sheet: Handle::default(),
+            sheet_path,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Playback state of a clip, a copy of its frames plus a cursor, so it can be inspected, saved and
+/// restored.
+#[derive(Component, Debug, Clone, Reflect, FromReflect)]
+#[reflect(Component)]
+pub struct Animation {
+    /// Playback rate multiplier of this entity, kept when the clip changes.
+    pub speed: f32,
+    frames: Vec<ClipFrame>,
+    mode: PlaybackMode,
+    cursor: usize,
+    elapsed: f32,
+    backwards: bool,
+    // events of the frames reached since the last call to animate
+    events: Vec<String>,
+    finished: bool,
+    // clip of the state being played, or played next when a transition is running
+    state_clip: String,
+    in_transition: bool,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::from_clip(&Clip::default())
+    }
+}
+
+impl Animation {
+    pub fn from_clip(clip: &Clip) -> Self {
+        Self {
+            speed: 1.,
+            frames: clip.frames.clone(),
+            mode: clip.mode,
+            cursor: 0,
+            elapsed: 0.,
+            backwards: false,
+            events: clip
+                .frames
+                .first()
+                .map(|frame| frame.events.clone())
+                .unwrap_or_default(),
+            finished: clip.mode == PlaybackMode::HoldLastFrame && clip.frames.len() == 1,
+            state_clip: String::new(),
+            in_transition: false,
+        }
+    }
+
+    /// Whether the clip of the current state is done playing, transitions leading to it never
+    /// count as finished.
+    pub fn is_finished(&self) -> bool {
+        self.finished && !self.in_transition
+    }
+
+    pub fn current(&self) -> Option<&ClipFrame> {
+        self.frames.get(self.cursor)
+    }
+
+    /// Position of the current frame in the clip.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Jumps to the frame at `cursor` in the clip, from its start.
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.frames.len().saturating_sub(1));
+        self.elapsed = 0.;
+    }
+
+    /// Index in the texture atlas of the frame currently shown.
+    pub fn frame(&self) -> Option<usize> {
+        self.current().map(|frame| frame.index)
+    }
+
+    /// Advances the animation by `delta` seconds, returns whether the frame shown changed.
+    pub fn tick(&mut self, delta: f32) -> bool {
+        // there is nothing to wait for in an animation without frames
+        if self.frames.is_empty() {
+            self.finished = true;
+            return false;
+        }
+
+        let start = self.cursor;
+        self.elapsed += delta;
+        // bounded so frames without duration cannot stall the game
+        for _ in 0..self.frames.len() {
+            let duration = self.frames[self.cursor].duration;
+            if self.elapsed < duration || !self.advance() {
+                break;
+            }
+            self.elapsed -= duration;
+            self.events
+                .extend(self.frames[self.cursor].events.iter().cloned());
+        }
+
+        self.cursor != start
+    }
+
+    /// Names of the events of every frame reached since the last call.
+    pub fn take_events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.events)
+    }
+
+    // moves to the next frame according to the playback mode, returns false when staying put
+    fn advance(&mut self) -> bool {
+        let last = self.frames.len() - 1;
+        match self.mode {
+            PlaybackMode::Loop => {
+                self.cursor = if self.cursor == last {
+                    0
+                } else {
+                    self.cursor + 1
+                };
+            }
+            PlaybackMode::Once => {
+                if self.cursor == last {
+                    self.finished = true;
+                    return false;
+                }
+                self.cursor += 1;
+            }
+            PlaybackMode::HoldLastFrame => {
+                if self.cursor == last {
+                    return false;
+                }
+                self.cursor += 1;
+                self.finished = self.cursor == last;
+            }
+            PlaybackMode::PingPong => {
+                if last == 0 {
+                    return false;
+                }
+                if self.cursor == last {
+                    self.backwards = true;
+                } else if self.cursor == 0 {
+                    self.backwards = false;
+                }
+                self.cursor = match self.backwards {
+                    true => self.cursor - 1,
+                    false => self.cursor + 1,
+                };
+            }
+            PlaybackMode::LoopFrom(loop_start) => {
+                if self.cursor == last {
+                    self.finished = true;
+                    self.cursor = loop_start.min(last);
+                } else {
+                    self.cursor += 1;
+                }
+            }
+        }
+        true
+    }
+}
+
+fn load_animation_sheet<T: AnimationStateComponent>(
+    mut animation_handles: ResMut<AnimationHandles<T>>,
+    asset_server: Res<AssetServer>,
+) {
+    animation_handles.sheet = asset_server.load(animation_handles.sheet_path);
+}
+
+pub fn update_animation<T: AnimationStateComponent>(
+    animation_handles: Res<AnimationHandles<T>>,
+    animation_sheets: Res<Assets<AnimationSheet>>,
+    mut sheet_events: EventReader<AssetEvent<AnimationSheet>>,
+    mut animation_query: Query<(
+        &mut Animation,
+        &mut TextureAtlasSprite,
+        &mut Handle<TextureAtlas>,
+        Ref<T>,
+    )>,
+) {
+    // a sheet that just finished loading, was edited on disk or was packed is picked up again by
+    // every animation using it
+    let sheet_reloaded = sheet_events.iter().any(|event| match event {
+        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+            *handle == animation_handles.sheet
+        }
+        AssetEvent::Removed { .. } => false,
+    });
+
+    let Some(sheet) = animation_sheets.get(&animation_handles.sheet) else {
+        return;
+    };
+
+    for (mut animation, mut texture_atlas_sprite, mut texture_atlas_handle, animation_state) in
+        animation_query.iter_mut()
+    {
+        let transition_over = animation.in_transition && animation.finished;
+        if !sheet_reloaded && !animation_state.is_changed() && !transition_over {
+            continue;
+        }
+
+        let state_clip = animation_state.clip_name();
+        let Some(target) = sheet.clips.get(state_clip) else {
+            warn!("no {} clip in the animation sheet", state_clip);
+            continue;
+        };
+
+        // a clip that is still the same length only gets its new frames, so packing the sheet or
+        // tweaking it on disk does not restart what is playing
+        let same_clip = !animation_state.is_changed()
+            && !animation.in_transition
+            && animation.state_clip == state_clip
+            && animation.mode == target.mode
+            && animation.frames.len() == target.frames.len();
+        if same_clip {
+            animation.frames = target.frames.clone();
+            *texture_atlas_handle = target.atlas.clone();
+            if let Some(index) = animation.frame() {
+                texture_atlas_sprite.index = index;
+            }
+            continue;
+        }
+
+        // reloads and the end of a transition go straight to the clip of the state
+        let transition = match animation_state.is_changed() && !sheet_reloaded {
+            true => sheet.transition(&animation.state_clip, state_clip),
+            false => None,
+        };
+        let clip = transition.unwrap_or(target);
+
+        let speed = animation.speed;
+        *animation = Animation::from_clip(clip);
+        animation.speed = speed;
+        animation.state_clip = state_clip.to_string();
+        animation.in_transition = transition.is_some();
+        *texture_atlas_handle = clip.atlas.clone();
+        if let Some(anchor) = clip.anchor {
+            texture_atlas_sprite.anchor = Anchor::Custom(anchor);
+        }
+        if let Some(index) = animation.frame() {
+            texture_atlas_sprite.index = index;
+        }
+    }
+}
+
+pub fn animate(
+    time: Res<Time>,
+    time_scale: Res<AnimationTimeScale>,
+    mut animation_events: EventWriter<AnimationEvent>,
+    mut animation_query: Query<(Entity, &mut Animation, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut animation, mut texture_atlas_sprite) in animation_query.iter_mut() {
+        let delta = time.delta_seconds() * time_scale.0 * animation.speed;
+        if animation.tick(delta) {
+            if let Some(index) = animation.frame() {
+                texture_atlas_sprite.index = index;
+            }
+        }
+
+        for name in animation.take_events() {
+            animation_events.send(AnimationEvent { entity, name });
+        }
+    }
+}
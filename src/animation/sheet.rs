@@ -0,0 +1,277 @@
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use super::{aseprite::AsepriteLoader, plugin::Animation};
+
+pub struct AnimationSheetPlugin;
+
+impl Plugin for AnimationSheetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<AnimationSheet>()
+            .add_event::<AnimationEvent>()
+            .init_resource::<AnimationTimeScale>()
+            .register_type::<Animation>()
+            .register_type::<PlaybackMode>()
+            .register_type::<ClipFrame>()
+            .register_type::<FrameBox>()
+            .register_type::<Vec<ClipFrame>>()
+            .register_type::<Option<FrameBox>>()
+            .register_type::<Vec<String>>()
+            .init_asset_loader::<AnimationSheetLoader>()
+            .init_asset_loader::<AsepriteLoader>();
+    }
+}
+
+/// Playback rate multiplier applied to every animation, for slow motion or pausing.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct AnimationTimeScale(pub f32);
+
+impl Default for AnimationTimeScale {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Reflect, FromReflect)]
+pub enum PlaybackMode {
+    /// Starts over after the last frame, never finishes.
+    #[default]
+    Loop,
+    /// Stops on the last frame and finishes once it has been shown for its whole duration.
+    Once,
+    /// Bounces between the first and the last frame, never finishes.
+    PingPong,
+    /// Stops on the last frame and finishes as soon as it is shown.
+    HoldLastFrame,
+    /// Plays through once then keeps looping from the given frame, finishes at the end of the
+    /// first pass.
+    LoopFrom(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect)]
+pub struct ClipFrame {
+    pub index: usize,
+    pub duration: f32,
+    /// Names of the [`AnimationEvent`]s sent when the frame is reached.
+    pub events: Vec<String>,
+    /// Area dealing damage while the frame is shown.
+    pub hitbox: Option<FrameBox>,
+    /// Area taking damage while the frame is shown.
+    pub hurtbox: Option<FrameBox>,
+}
+
+/// Box relative to the entity playing the animation, drawn facing right.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct FrameBox {
+    pub offset: Vec2,
+    pub half_size: Vec2,
+}
+
+/// Sent by `animate` when an animation reaches a frame tagged with `name`, like "hit_start" or
+/// "footstep".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationEvent {
+    pub entity: Entity,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Clip {
+    pub atlas: Handle<TextureAtlas>,
+    pub frames: Vec<ClipFrame>,
+    pub mode: PlaybackMode,
+    pub anchor: Option<Vec2>,
+}
+
+/// Clip played once when going from the `from` state to the `to` state, before the clip of `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Transition {
+    pub from: String,
+    pub to: String,
+    pub clip: String,
+}
+
+/// Every clip of a character, keyed by the name of the state playing it.
+#[derive(Debug, TypeUuid)]
+#[uuid = "8d3c1f5e-6a2b-4c7e-9f10-2b5d4e8a7c31"]
+pub struct AnimationSheet {
+    pub clips: HashMap<String, Clip>,
+    pub transitions: Vec<Transition>,
+    /// Set once the packing step is done with the sheet, loaders always leave it false.
+    pub packed: bool,
+}
+
+impl AnimationSheet {
+    pub fn transition(&self, from: &str, to: &str) -> Option<&Clip> {
+        self.transitions
+            .iter()
+            .find(|transition| transition.from == from && transition.to == to)
+            .and_then(|transition| self.clips.get(&transition.clip))
+    }
+}
+
+#[derive(Deserialize)]
+struct AtlasDefinition {
+    path: String,
+    tile_size: (f32, f32),
+    columns: usize,
+    rows: usize,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct FrameBoxDefinition {
+    offset: (f32, f32),
+    half_size: (f32, f32),
+}
+
+impl From<FrameBoxDefinition> for FrameBox {
+    fn from(definition: FrameBoxDefinition) -> Self {
+        Self {
+            offset: Vec2::new(definition.offset.0, definition.offset.1),
+            half_size: Vec2::new(definition.half_size.0, definition.half_size.1),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ClipDefinition {
+    atlas: String,
+    frames: Vec<usize>,
+    fps: f32,
+    // per frame overrides of the duration given by fps, keyed by position in the clip
+    #[serde(default)]
+    durations: HashMap<usize, f32>,
+    // event names sent when a frame is reached, keyed by position in the clip
+    #[serde(default)]
+    events: HashMap<usize, Vec<String>>,
+    #[serde(default)]
+    hitboxes: HashMap<usize, FrameBoxDefinition>,
+    #[serde(default)]
+    hurtboxes: HashMap<usize, FrameBoxDefinition>,
+    // hurtbox of the frames without one in hurtboxes
+    #[serde(default)]
+    hurtbox: Option<FrameBoxDefinition>,
+    mode: PlaybackMode,
+    #[serde(default)]
+    anchor: Option<(f32, f32)>,
+}
+
+#[derive(Deserialize)]
+struct SheetDefinition {
+    atlases: HashMap<String, AtlasDefinition>,
+    clips: HashMap<String, ClipDefinition>,
+    #[serde(default)]
+    transitions: Vec<Transition>,
+}
+
+#[derive(Default)]
+pub struct AnimationSheetLoader;
+
+impl AssetLoader for AnimationSheetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let definition: SheetDefinition = ron::de::from_bytes(bytes)?;
+
+            let mut dependencies = Vec::new();
+            let mut atlases = HashMap::new();
+            let mut atlas_lengths = HashMap::new();
+            for (name, atlas) in definition.atlases {
+                let image_path = AssetPath::new(atlas.path.into(), None);
+                let texture_atlas = TextureAtlas::from_grid(
+                    load_context.get_handle(image_path.clone()),
+                    Vec2::new(atlas.tile_size.0, atlas.tile_size.1),
+                    atlas.columns,
+                    atlas.rows,
+                    None,
+                    None,
+                );
+                let handle = load_context.set_labeled_asset(&name, LoadedAsset::new(texture_atlas));
+                atlas_lengths.insert(name.clone(), atlas.columns * atlas.rows);
+                atlases.insert(name, handle);
+                dependencies.push(image_path);
+            }
+
+            let mut clips = HashMap::new();
+            for (name, clip) in definition.clips {
+                let Some(atlas) = atlases.get(&clip.atlas) else {
+                    return Err(bevy::asset::Error::msg(format!(
+                        "clip {} uses undeclared atlas {}",
+                        name, clip.atlas
+                    )));
+                };
+                if let Some(index) = clip
+                    .frames
+                    .iter()
+                    .find(|&&index| index >= atlas_lengths[&clip.atlas])
+                {
+                    return Err(bevy::asset::Error::msg(format!(
+                        "clip {} uses frame {} but atlas {} only has {} frames",
+                        name, index, clip.atlas, atlas_lengths[&clip.atlas]
+                    )));
+                }
+                clips.insert(
+                    name,
+                    Clip {
+                        atlas: atlas.clone(),
+                        frames: clip
+                            .frames
+                            .iter()
+                            .enumerate()
+                            .map(|(position, &index)| ClipFrame {
+                                index,
+                                duration: clip
+                                    .durations
+                                    .get(&position)
+                                    .copied()
+                                    .unwrap_or(1. / clip.fps),
+                                events: clip.events.get(&position).cloned().unwrap_or_default(),
+                                hitbox: clip.hitboxes.get(&position).map(|&hitbox| hitbox.into()),
+                                hurtbox: clip
+                                    .hurtboxes
+                                    .get(&position)
+                                    .or(clip.hurtbox.as_ref())
+                                    .map(|&hurtbox| hurtbox.into()),
+                            })
+                            .collect(),
+                        mode: clip.mode,
+                        anchor: clip.anchor.map(|(x, y)| Vec2::new(x, y)),
+                    },
+                );
+            }
+
+            if let Some(transition) = definition
+                .transitions
+                .iter()
+                .find(|transition| !clips.contains_key(&transition.clip))
+            {
+                return Err(bevy::asset::Error::msg(format!(
+                    "transition from {} to {} uses undeclared clip {}",
+                    transition.from, transition.to, transition.clip
+                )));
+            }
+
+            load_context.set_default_asset(
+                LoadedAsset::new(AnimationSheet {
+                    clips,
+                    transitions: definition.transitions,
+                    packed: false,
+                })
+                .with_dependencies(dependencies),
+            );
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
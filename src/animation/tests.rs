@@ -0,0 +1,485 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    time::TimeUpdateStrategy,
+    utils::HashMap,
+};
+use strum::EnumIter;
+
+use crate::{test_support::FRAME, GameSet, GameSetsPlugin};
+
+use super::{
+    animate, packing::pack_animation_sheet, update_animation, validation::sheet_errors, Animation,
+    AnimationBundle, AnimationEvent, AnimationHandles, AnimationSheet, AnimationState,
+    AnimationTimeScale, Clip, ClipFrame, PlaybackMode, SpriteLayer, SpriteLayers,
+    SpriteLayersPlugin, Transition, VisualTransform,
+};
+
+fn clip(mode: PlaybackMode, durations: &[f32]) -> Clip {
+    Clip {
+        atlas: Handle::default(),
+        frames: durations
+            .iter()
+            .enumerate()
+            .map(|(index, &duration)| ClipFrame {
+                index,
+                duration,
+                events: Vec::new(),
+                hitbox: None,
+                hurtbox: None,
+            })
+            .collect(),
+        mode,
+        anchor: None,
+    }
+}
+
+// frame shown after every tick of `delta` seconds
+fn play(animation: &mut Animation, delta: f32, ticks: usize) -> Vec<usize> {
+    (0..ticks)
+        .map(|_| {
+            animation.tick(delta);
+            animation.frame().unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn loop_wraps_around() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::Loop, &[0.125; 3]));
+    assert_eq!(play(&mut animation, 0.125, 4), [1, 2, 0, 1]);
+    assert!(!animation.is_finished());
+}
+
+#[test]
+fn frames_keep_their_own_duration() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::Loop, &[0.375, 0.125]));
+    assert_eq!(play(&mut animation, 0.125, 5), [0, 0, 1, 0, 0]);
+}
+
+#[test]
+fn once_finishes_after_the_last_frame() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::Once, &[0.125; 3]));
+    assert_eq!(play(&mut animation, 0.125, 2), [1, 2]);
+    assert!(!animation.is_finished());
+
+    assert_eq!(play(&mut animation, 0.125, 2), [2, 2]);
+    assert!(animation.is_finished());
+}
+
+#[test]
+fn hold_last_frame_finishes_when_reaching_it() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::HoldLastFrame, &[0.125; 3]));
+    assert_eq!(play(&mut animation, 0.125, 2), [1, 2]);
+    assert!(animation.is_finished());
+    assert_eq!(play(&mut animation, 0.125, 2), [2, 2]);
+}
+
+#[test]
+fn ping_pong_reverses_at_both_ends() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::PingPong, &[0.125; 3]));
+    assert_eq!(play(&mut animation, 0.125, 6), [1, 2, 1, 0, 1, 2]);
+}
+
+#[test]
+fn loop_from_skips_the_intro_after_the_first_pass() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::LoopFrom(1), &[0.125; 3]));
+    assert_eq!(play(&mut animation, 0.125, 2), [1, 2]);
+    assert!(!animation.is_finished());
+
+    assert_eq!(play(&mut animation, 0.125, 3), [1, 2, 1]);
+    assert!(animation.is_finished());
+}
+
+#[test]
+fn frames_without_duration_do_not_stall() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::Loop, &[0.0; 4]));
+    animation.tick(0.125);
+    assert!(animation.frame().is_some());
+}
+
+#[test]
+fn events_are_collected_for_every_frame_reached() {
+    let mut clip = clip(PlaybackMode::Loop, &[0.125; 3]);
+    clip.frames[0].events.push("footstep".to_string());
+    clip.frames[1].events.push("hit_start".to_string());
+    clip.frames[2].events.push("hit_end".to_string());
+
+    let mut animation = Animation::from_clip(&clip);
+    assert_eq!(animation.take_events(), ["footstep"]);
+
+    // a long frame skips several frames at once, none of their events may be lost
+    animation.tick(0.25);
+    assert_eq!(animation.take_events(), ["hit_start", "hit_end"]);
+    assert!(animation.take_events().is_empty());
+}
+
+#[test]
+fn transitions_are_looked_up_by_state_pair() {
+    let sheet = AnimationSheet {
+        clips: HashMap::from_iter([
+            ("Idle".to_string(), clip(PlaybackMode::Loop, &[0.125])),
+            ("Crouching".to_string(), clip(PlaybackMode::Loop, &[0.125])),
+            (
+                "CrouchTransition".to_string(),
+                clip(PlaybackMode::Once, &[0.125; 2]),
+            ),
+        ]),
+        transitions: vec![Transition {
+            from: "Idle".to_string(),
+            to: "Crouching".to_string(),
+            clip: "CrouchTransition".to_string(),
+        }],
+        packed: false,
+    };
+
+    let transition = sheet.transition("Idle", "Crouching").unwrap();
+    assert_eq!(transition.frames.len(), 2);
+    assert!(sheet.transition("Crouching", "Idle").is_none());
+}
+
+#[test]
+fn stretching_keeps_the_pivot_in_place() {
+    let size = Vec2::new(120.0, 80.0);
+    let pivot = Vec2::new(0.0, -20.0);
+    let mut visual = VisualTransform::with_pivot(pivot);
+    visual.scale = Vec2::new(0.5, 2.0);
+
+    // where the texture point under the pivot ends up once drawn with the new size and anchor
+    let texture_point = pivot / size;
+    for flip_x in [false, true] {
+        let anchor = visual.anchor(size, flip_x);
+        assert_eq!((texture_point - anchor) * size * visual.scale, pivot);
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+enum TestState {
+    Idle,
+    Crouching,
+}
+
+impl AnimationState for TestState {
+    fn clip_name(&self) -> &'static str {
+        match self {
+            Self::Idle => "Idle",
+            Self::Crouching => "Crouching",
+        }
+    }
+}
+
+// clip whose frames start at `first` in the atlas
+fn clip_from(first: usize, mode: PlaybackMode, durations: &[f32]) -> Clip {
+    let mut clip = clip(mode, durations);
+    for frame in clip.frames.iter_mut() {
+        frame.index += first;
+    }
+    clip
+}
+
+#[test]
+fn transitions_play_before_the_clip_of_the_new_state() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_asset::<TextureAtlas>()
+        .add_asset::<AnimationSheet>()
+        .add_event::<AnimationEvent>()
+        .init_resource::<AnimationTimeScale>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .add_plugin(GameSetsPlugin)
+        .add_system(update_animation::<TestState>.in_set(GameSet::AfterUpdate))
+        .add_system(animate.in_set(GameSet::Render));
+
+    // every frame lasts three game frames
+    let duration = FRAME.as_secs_f32() * 3.0;
+    let sheet = app
+        .world
+        .resource_mut::<Assets<AnimationSheet>>()
+        .add(AnimationSheet {
+            clips: HashMap::from_iter([
+                (
+                    "Idle".to_string(),
+                    clip_from(0, PlaybackMode::Loop, &[duration]),
+                ),
+                (
+                    "Crouching".to_string(),
+                    clip_from(10, PlaybackMode::Loop, &[duration; 2]),
+                ),
+                (
+                    "CrouchTransition".to_string(),
+                    clip_from(5, PlaybackMode::Once, &[duration; 2]),
+                ),
+            ]),
+            transitions: vec![Transition {
+                from: "Idle".to_string(),
+                to: "Crouching".to_string(),
+                clip: "CrouchTransition".to_string(),
+            }],
+            packed: false,
+        });
+    let mut handles = AnimationHandles::<TestState>::new("");
+    handles.sheet = sheet;
+    app.insert_resource(handles);
+
+    let entity = app
+        .world
+        .spawn((
+            AnimationBundle::new(TestState::Idle),
+            TextureAtlasSprite::default(),
+            Handle::<TextureAtlas>::default(),
+        ))
+        .id();
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world.get::<TextureAtlasSprite>(entity).unwrap().index,
+        0
+    );
+
+    *app.world.get_mut::<TestState>(entity).unwrap() = TestState::Crouching;
+    let mut shown = Vec::new();
+    for _ in 0..20 {
+        app.update();
+        let entity = app.world.entity(entity);
+        // gameplay only ever sees the state it asked for
+        assert_eq!(entity.get::<TestState>(), Some(&TestState::Crouching));
+        let index = entity.get::<TextureAtlasSprite>().unwrap().index;
+        if shown.last() != Some(&index) {
+            shown.push(index);
+        }
+        if index < 10 {
+            assert!(!entity.get::<Animation>().unwrap().is_finished());
+        }
+    }
+    assert_eq!(shown[..4], [5, 6, 10, 11]);
+}
+
+#[test]
+fn animations_can_be_snapshot_and_restored() {
+    let mut animation = Animation::from_clip(&clip(PlaybackMode::PingPong, &[0.125; 3]));
+    play(&mut animation, 0.125, 3);
+
+    let snapshot = animation.clone_value();
+    play(&mut animation, 0.125, 2);
+    assert_eq!(animation.frame(), Some(1));
+
+    let mut restored = Animation::from_reflect(snapshot.as_ref()).unwrap();
+    assert_eq!(restored.frame(), Some(1));
+    assert_eq!(play(&mut restored, 0.125, 2), [0, 1]);
+}
+
+#[test]
+fn layers_follow_the_base_sprite() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(SpriteLayersPlugin)
+        .add_plugin(GameSetsPlugin);
+    let base = app
+        .world
+        .spawn((
+            TextureAtlasSprite::new(5),
+            SpriteLayers::default().with_layer(SpriteLayer::new("cape", Handle::default(), -0.1)),
+        ))
+        .id();
+    app.update();
+    app.update();
+
+    app.world.get_mut::<TextureAtlasSprite>(base).unwrap().index = 7;
+    app.update();
+
+    let mut layer_query = app
+        .world
+        .query_filtered::<(&TextureAtlasSprite, &Transform), With<Parent>>();
+    let (layer_sprite, transform) = layer_query.single(&app.world);
+    assert_eq!(layer_sprite.index, 7);
+    assert_eq!(transform.translation.z, -0.1);
+}
+
+// image 4 pixels high filled with a single shade of red
+fn image(app: &mut App, width: u32, red: u8) -> Handle<Image> {
+    app.world
+        .resource_mut::<Assets<Image>>()
+        .add(Image::new_fill(
+            Extent3d {
+                width,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[red, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        ))
+}
+
+// atlas declaring `columns` 4x4 frames on `image`
+fn atlas(app: &mut App, image: Handle<Image>, columns: usize) -> Handle<TextureAtlas> {
+    let atlas = TextureAtlas::from_grid(image, Vec2::splat(4.0), columns, 1, None, None);
+    app.world.resource_mut::<Assets<TextureAtlas>>().add(atlas)
+}
+
+fn strip(app: &mut App, columns: usize, red: u8) -> Handle<TextureAtlas> {
+    let image = image(app, 4 * columns as u32, red);
+    atlas(app, image, columns)
+}
+
+#[test]
+fn packing_moves_every_frame_to_the_packed_atlas() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>()
+        .add_asset::<AnimationSheet>()
+        .add_system(pack_animation_sheet::<TestState>);
+
+    let idle = strip(&mut app, 2, 100);
+    let crouching = strip(&mut app, 3, 200);
+    let mut idle_clip = clip(PlaybackMode::Loop, &[0.125; 2]);
+    idle_clip.atlas = idle.clone();
+    let mut crouching_clip = clip(PlaybackMode::Loop, &[0.125; 3]);
+    crouching_clip.atlas = crouching.clone();
+    let sheet = app
+        .world
+        .resource_mut::<Assets<AnimationSheet>>()
+        .add(AnimationSheet {
+            clips: HashMap::from_iter([
+                ("Idle".to_string(), idle_clip),
+                ("Crouching".to_string(), crouching_clip),
+            ]),
+            transitions: Vec::new(),
+            packed: false,
+        });
+    let mut handles = AnimationHandles::<TestState>::new("");
+    handles.sheet = sheet.clone();
+    app.insert_resource(handles);
+    app.update();
+
+    let sheet = app
+        .world
+        .resource::<Assets<AnimationSheet>>()
+        .get(&sheet)
+        .unwrap();
+    assert!(sheet.packed);
+    let packed_atlas = &sheet.clips["Idle"].atlas;
+    assert_eq!(&sheet.clips["Crouching"].atlas, packed_atlas);
+    let atlases = app.world.resource::<Assets<TextureAtlas>>();
+    let packed = atlases.get(packed_atlas).unwrap();
+    let image = app
+        .world
+        .resource::<Assets<Image>>()
+        .get(&packed.texture)
+        .unwrap();
+    let width = image.size().x as usize;
+
+    for (name, strip, red) in [("Idle", &idle, 100), ("Crouching", &crouching, 200)] {
+        let strip = atlases.get(strip).unwrap();
+        let frames = &sheet.clips[name].frames;
+        let first = packed.textures[frames[0].index];
+        for (position, frame) in frames.iter().enumerate() {
+            // frames keep their place inside the strip, wherever the strip went
+            let rect = packed.textures[frame.index];
+            assert_eq!(rect.min - first.min, strip.textures[position].min);
+            assert_eq!(rect.size(), Vec2::splat(4.0));
+            let pixel = (rect.min.y as usize * width + rect.min.x as usize) * 4;
+            assert_eq!(image.data[pixel], red);
+        }
+    }
+}
+
+fn asset_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>();
+    app
+}
+
+fn validate(app: &App, clips: Vec<(&str, Clip)>) -> Vec<String> {
+    let sheet = AnimationSheet {
+        clips: clips
+            .into_iter()
+            .map(|(name, clip)| (name.to_string(), clip))
+            .collect(),
+        transitions: Vec::new(),
+        packed: false,
+    };
+    sheet_errors(
+        &sheet,
+        ["Idle", "Crouching"].into_iter(),
+        app.world.resource::<Assets<TextureAtlas>>(),
+        app.world.resource::<Assets<Image>>(),
+        app.world.resource::<AssetServer>(),
+    )
+    .unwrap()
+}
+
+fn clip_on(atlas: &Handle<TextureAtlas>, frames: usize) -> Clip {
+    Clip {
+        atlas: atlas.clone(),
+        ..clip(PlaybackMode::Loop, &vec![0.125; frames])
+    }
+}
+
+#[test]
+fn sound_sheets_have_no_errors() {
+    let mut app = asset_app();
+    // cells past the declared grid are fine
+    let padded = image(&mut app, 16, 0);
+    let atlas = atlas(&mut app, padded, 2);
+
+    let errors = validate(
+        &app,
+        vec![
+            ("Idle", clip_on(&atlas, 2)),
+            ("Crouching", clip_on(&atlas, 1)),
+        ],
+    );
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn frames_do_not_have_to_fill_the_image() {
+    let mut app = asset_app();
+    // 10 pixels wide, two 4x4 frames and a leftover strip like exported sheets have
+    let uneven = image(&mut app, 10, 0);
+    let atlas = atlas(&mut app, uneven, 2);
+
+    let errors = validate(
+        &app,
+        vec![
+            ("Idle", clip_on(&atlas, 2)),
+            ("Crouching", clip_on(&atlas, 1)),
+        ],
+    );
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn every_mistake_is_reported() {
+    let mut app = asset_app();
+    let strip = strip(&mut app, 2, 0);
+    let short = image(&mut app, 8, 0);
+    let short = atlas(&mut app, short, 3);
+
+    let errors = validate(
+        &app,
+        vec![("Idle", clip_on(&strip, 3)), ("Short", clip_on(&short, 1))],
+    );
+    let expected = [
+        "no clip for state Crouching",
+        "clip Idle uses frame 2 but its atlas only has 2 frames",
+        "is 8x4 but frame 2 of clip Short goes up to 12x4",
+    ];
+    assert_eq!(errors.len(), expected.len(), "{:?}", errors);
+    for expected in expected {
+        assert!(
+            errors.iter().any(|error| error.contains(expected)),
+            "{:?} not in {:?}",
+            expected,
+            errors
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use super::{
+    plugin::{AnimationHandles, AnimationStateComponent},
+    sheet::AnimationSheet,
+};
+
+/// Checks a sheet against its images and its states once everything has loaded, so mistakes are
+/// reported when the game starts rather than as a panic in the renderer.
+pub fn validate_animation_sheet<T: AnimationStateComponent>(
+    mut validated: Local<bool>,
+    mut sheet_events: EventReader<AssetEvent<AnimationSheet>>,
+    animation_handles: Res<AnimationHandles<T>>,
+    animation_sheets: Res<Assets<AnimationSheet>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    images: Res<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(sheet) = animation_sheets.get(&animation_handles.sheet) else {
+        return;
+    };
+    // a sheet edited on disk is checked again, packing it changes nothing worth checking
+    if sheet_events.iter().any(|event| match event {
+        AssetEvent::Modified { handle } => *handle == animation_handles.sheet,
+        _ => false,
+    }) && !sheet.packed
+    {
+        *validated = false;
+    }
+    if *validated {
+        return;
+    }
+    let sheet_path = asset_server
+        .get_handle_path(&animation_handles.sheet)
+        .map(|path| path.path().display().to_string())
+        .unwrap_or_default();
+
+    let Some(errors) = sheet_errors(
+        sheet,
+        T::iter().map(|state| state.clip_name()),
+        &texture_atlases,
+        &images,
+        &asset_server,
+    ) else {
+        return;
+    };
+
+    *validated = true;
+    for error in errors {
+        error!("{}: {}", sheet_path, error);
+    }
+}
+
+/// Mistakes in `sheet` given the clips its states play, or `None` while some of its images are
+/// still loading.
+pub(super) fn sheet_errors(
+    sheet: &AnimationSheet,
+    clip_names: impl Iterator<Item = &'static str>,
+    texture_atlases: &Assets<TextureAtlas>,
+    images: &Assets<Image>,
+    asset_server: &AssetServer,
+) -> Option<Vec<String>> {
+    let mut errors = Vec::new();
+
+    for clip_name in clip_names {
+        if !sheet.clips.contains_key(clip_name) {
+            errors.push(format!("no clip for state {}", clip_name));
+        }
+    }
+
+    for (name, clip) in sheet.clips.iter() {
+        let texture_atlas = texture_atlases.get(&clip.atlas)?;
+        let image_path = asset_server
+            .get_handle_path(&texture_atlas.texture)
+            .map(|path| path.path().display().to_string())
+            .unwrap_or_default();
+        let Some(image) = images.get(&texture_atlas.texture) else {
+            match asset_server.get_load_state(&texture_atlas.texture) {
+                LoadState::Failed => {
+                    errors.push(format!("clip {} uses missing image {}", name, image_path));
+                    continue;
+                }
+                // images finish loading after the sheet, wait for them
+                _ => return None,
+            }
+        };
+
+        // grids, aseprite exports and packed sheets all lay frames out differently, they only
+        // have to stay on the image
+        let image_size = image.size();
+        if let Some((index, rect)) =
+            texture_atlas.textures.iter().enumerate().find(|(_, rect)| {
+                rect.min.cmplt(Vec2::ZERO).any() || rect.max.cmpgt(image_size).any()
+            })
+        {
+            errors.push(format!(
+                "image {} is {}x{} but frame {} of clip {} goes up to {}x{}",
+                image_path, image_size.x, image_size.y, index, name, rect.max.x, rect.max.y,
+            ));
+        }
+
+        for frame in clip.frames.iter() {
+            if frame.index >= texture_atlas.len() {
+                errors.push(format!(
+                    "clip {} uses frame {} but its atlas only has {} frames",
+                    name,
+                    frame.index,
+                    texture_atlas.len()
+                ));
+            }
+        }
+    }
+
+    Some(errors)
+}
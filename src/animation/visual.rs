@@ -0,0 +1,92 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::{kinematics::Orientation, GameSet};
+
+use super::plugin::animate;
+
+/// Applies [`VisualTransform`]s to the sprites once their frame is known.
+pub struct VisualTransformPlugin;
+
+impl Plugin for VisualTransformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            apply_visual_transforms
+                .in_set(GameSet::Render)
+                .after(animate),
+        );
+    }
+}
+
+/// How a sprite is drawn, apart from the `Transform` the simulation owns. The sprite faces its
+/// [`Orientation`] and is stretched by `scale` around `pivot`, through its size, anchor and flip,
+/// so colliders and sensors never see it.
+#[derive(Component, Debug, Clone)]
+pub struct VisualTransform {
+    /// 1 leaves the sprite as drawn.
+    pub scale: Vec2,
+    /// Point relative to the entity origin, facing right, that stays in place while stretching.
+    pub pivot: Vec2,
+    // anchor set by the animation, and the one written over it
+    anchor: Vec2,
+    applied: Option<Vec2>,
+}
+
+impl Default for VisualTransform {
+    fn default() -> Self {
+        Self::with_pivot(Vec2::ZERO)
+    }
+}
+
+impl VisualTransform {
+    pub fn with_pivot(pivot: Vec2) -> Self {
+        Self {
+            scale: Vec2::ONE,
+            pivot,
+            anchor: Vec2::ZERO,
+            applied: None,
+        }
+    }
+
+    /// Anchor that keeps the pivot in place on a sprite of `size` stretched by `scale`.
+    pub fn anchor(&self, size: Vec2, flip_x: bool) -> Vec2 {
+        let mut anchor = self.anchor + self.pivot / size - self.pivot / (size * self.scale);
+        // a flipped texture mirrors around the anchor, so the anchor is mirrored as well
+        if flip_x {
+            anchor.x = -anchor.x;
+        }
+        anchor
+    }
+}
+
+pub fn apply_visual_transforms(
+    atlases: Res<Assets<TextureAtlas>>,
+    mut sprite_query: Query<(
+        &mut VisualTransform,
+        &mut TextureAtlasSprite,
+        &Handle<TextureAtlas>,
+        Option<&Orientation>,
+    )>,
+) {
+    for (mut visual, mut sprite, atlas, orientation) in sprite_query.iter_mut() {
+        let Some(size) = atlases
+            .get(atlas)
+            .and_then(|atlas| atlas.textures.get(sprite.index))
+            .map(|rect| rect.size())
+        else {
+            continue;
+        };
+
+        // the animation only writes the anchor when a clip starts
+        let current = sprite.anchor.as_vec();
+        if visual.applied != Some(current) {
+            visual.anchor = current;
+        }
+
+        let flip_x = orientation == Some(&Orientation::Left);
+        let anchor = visual.anchor(size, flip_x);
+        sprite.flip_x = flip_x;
+        sprite.custom_size = (visual.scale != Vec2::ONE).then(|| size * visual.scale);
+        sprite.anchor = Anchor::Custom(anchor);
+        visual.applied = Some(anchor);
+    }
+}
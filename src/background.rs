@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::GameSet;
+use crate::{
+    ambient::{AmbientTint, TILE_AMBIENT_STRENGTH},
+    GameSet,
+};
 
 pub struct BackgroundPlugin;
 
@@ -19,6 +22,8 @@ impl Plugin for BackgroundPlugin {
 const FAR_CLIP_PLANE: f32 = -999.9;
 const BACKGROUND_SPRITE_WIDTH: f32 = 688.0;
 const LOOP_SAFETY_MARGIN: f32 = 50.0;
+// how much further than the level the farthest layer goes with the ambient light
+const PARALLAX_AMBIENT_SHIFT: f32 = 0.6;
 
 #[derive(Component, Default)]
 struct ParallaxBackground {
@@ -114,6 +119,10 @@ fn display_backgrounds(
                         index,
                         anchor: parallax_background.anchor,
                     },
+                    AmbientTint::new(
+                        TILE_AMBIENT_STRENGTH
+                            + PARALLAX_AMBIENT_SHIFT * parallax_background.z_index / FAR_CLIP_PLANE,
+                    ),
                     SpriteBundle {
                         texture: parallax_background.texture.clone(),
                         transform: Transform {
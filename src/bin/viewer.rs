@@ -0,0 +1,574 @@
+//! Plays every clip found in the assets folder without going through the game.
+//!
+//! Up and Down pick a clip, Space pauses, Left and Right step through the frames, + and - change
+//! the playback speed, [ and ] shorten or lengthen the frame shown and Enter restarts the clip.
+//! Loose strips that no sheet uses are cut in frames of `--frame-width` pixels, 120 by default.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{prelude::*, sprite::Anchor};
+use platformer_2d::animation::{
+    animate, Animation, AnimationSheet, AnimationSheetPlugin, AnimationTimeScale, Clip, ClipFrame,
+    FrameBox,
+};
+
+const ASSETS_DIR: &str = "assets";
+const SHEET_EXTENSIONS: [&str; 2] = [".anim.ron", ".aseprite.json"];
+const DEFAULT_FRAME_WIDTH: f32 = 120.0;
+const STRIP_FRAME_DURATION: f32 = 0.1;
+// the preview is drawn this many times bigger than in game
+const SCALE: f32 = 4.0;
+const DURATION_STEP: f32 = 0.01;
+const SPEED_STEP: f32 = 0.25;
+const FONT_SIZE: f32 = 16.0;
+const LISTED_ENTRIES: usize = 20;
+
+fn main() {
+    let frame_width = std::env::args()
+        .skip_while(|arg| arg != "--frame-width")
+        .nth(1)
+        .and_then(|width| width.parse().ok())
+        .unwrap_or(DEFAULT_FRAME_WIDTH);
+
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                .set(AssetPlugin {
+                    watch_for_changes: true,
+                    ..default()
+                }),
+        )
+        .add_plugin(AnimationSheetPlugin)
+        .insert_resource(Catalog {
+            frame_width,
+            ..default()
+        })
+        .init_resource::<Viewer>()
+        .add_startup_system(setup)
+        .add_system(cut_strips)
+        .add_system(select_entry.after(cut_strips))
+        .add_system(control_playback.after(select_entry))
+        .add_system(edit_timing.after(control_playback))
+        .add_system(animate.after(edit_timing))
+        .add_system(update_overlays.after(animate))
+        .add_system(update_info.after(animate))
+        .run();
+}
+
+/// Image that no sheet describes, played as a single row of frames.
+struct Strip {
+    path: String,
+    image: Handle<Image>,
+    clip: Option<Clip>,
+}
+
+#[derive(Resource, Default)]
+struct Catalog {
+    frame_width: f32,
+    sheets: Vec<(String, Handle<AnimationSheet>)>,
+    strips: Vec<Strip>,
+}
+
+/// Clip that can be previewed, with where it comes from so timing edits can be written back.
+struct Entry {
+    label: String,
+    clip: Clip,
+    source: EntrySource,
+}
+
+enum EntrySource {
+    Sheet(Handle<AnimationSheet>, String),
+    Strip(usize),
+}
+
+#[derive(Resource, Default)]
+struct Viewer {
+    selected: usize,
+    shown: Option<String>,
+    paused: bool,
+}
+
+#[derive(Component)]
+struct Preview;
+
+#[derive(Component)]
+enum Overlay {
+    FrameBounds,
+    PivotHorizontal,
+    PivotVertical,
+    Hitbox,
+    Hurtbox,
+}
+
+#[derive(Component)]
+struct InfoText;
+
+fn find_files(root: &Path, directory: &Path, files: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_files(root, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+// the same folder the asset server reads from when started with cargo
+fn assets_root() -> PathBuf {
+    std::env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(ASSETS_DIR)
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut catalog: ResMut<Catalog>) {
+    let root = assets_root();
+    let mut files = Vec::new();
+    find_files(&root, &root, &mut files);
+    files.sort();
+
+    let sheet_directories: Vec<String> = files
+        .iter()
+        .filter(|file| {
+            SHEET_EXTENSIONS
+                .iter()
+                .any(|extension| file.ends_with(extension))
+        })
+        .map(|file| parent_directory(file))
+        .collect();
+    for file in files.iter() {
+        if SHEET_EXTENSIONS
+            .iter()
+            .any(|extension| file.ends_with(extension))
+        {
+            catalog
+                .sheets
+                .push((file.clone(), asset_server.load(file.as_str())));
+        } else if file.ends_with(".png") && sheet_directories.contains(&parent_directory(file)) {
+            // only images next to a sheet, tilesets and backgrounds are not animations
+            catalog.strips.push(Strip {
+                path: file.clone(),
+                image: asset_server.load(file.as_str()),
+                clip: None,
+            });
+        }
+    }
+
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn((
+        Name::new("Preview"),
+        Preview,
+        Animation::default(),
+        SpriteSheetBundle {
+            transform: Transform::from_scale(Vec3::splat(SCALE)),
+            ..default()
+        },
+    ));
+
+    for (overlay, color, z) in [
+        (Overlay::FrameBounds, Color::rgba(1.0, 1.0, 1.0, 0.1), -1.0),
+        (Overlay::PivotHorizontal, Color::YELLOW, 1.0),
+        (Overlay::PivotVertical, Color::YELLOW, 1.0),
+        (Overlay::Hitbox, Color::rgba(1.0, 0.0, 0.0, 0.4), 2.0),
+        (Overlay::Hurtbox, Color::rgba(0.0, 1.0, 0.0, 0.4), 2.0),
+    ] {
+        commands.spawn((
+            overlay,
+            SpriteBundle {
+                sprite: Sprite { color, ..default() },
+                transform: Transform::from_xyz(0.0, 0.0, z),
+                ..default()
+            },
+        ));
+    }
+
+    commands.spawn((
+        InfoText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("font/arcade-classic.ttf"),
+                font_size: FONT_SIZE,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(8.0),
+                top: Val::Px(8.0),
+                ..default()
+            },
+            ..default()
+        }),
+    ));
+}
+
+fn parent_directory(file: &str) -> String {
+    file.rsplit_once('/')
+        .map(|(directory, _)| directory.to_string())
+        .unwrap_or_default()
+}
+
+/// Every clip of the loaded sheets followed by the strips no sheet uses, in a stable order.
+fn entries(
+    catalog: &Catalog,
+    animation_sheets: &Assets<AnimationSheet>,
+    texture_atlases: &Assets<TextureAtlas>,
+) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut used_images = Vec::new();
+
+    for (path, handle) in catalog.sheets.iter() {
+        let Some(sheet) = animation_sheets.get(handle) else {
+            continue;
+        };
+        let mut names: Vec<&String> = sheet.clips.keys().collect();
+        names.sort();
+        for name in names {
+            let clip = &sheet.clips[name];
+            if let Some(texture_atlas) = texture_atlases.get(&clip.atlas) {
+                used_images.push(texture_atlas.texture.clone());
+            }
+            entries.push(Entry {
+                label: format!("{} {}", path, name),
+                clip: clip.clone(),
+                source: EntrySource::Sheet(handle.clone(), name.clone()),
+            });
+        }
+    }
+
+    for (index, strip) in catalog.strips.iter().enumerate() {
+        let Some(clip) = &strip.clip else {
+            continue;
+        };
+        if used_images.contains(&strip.image) {
+            continue;
+        }
+        entries.push(Entry {
+            label: strip.path.clone(),
+            clip: clip.clone(),
+            source: EntrySource::Strip(index),
+        });
+    }
+
+    entries
+}
+
+fn cut_strips(
+    mut catalog: ResMut<Catalog>,
+    images: Res<Assets<Image>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    let frame_width = catalog.frame_width;
+    for strip in catalog
+        .strips
+        .iter_mut()
+        .filter(|strip| strip.clip.is_none())
+    {
+        let Some(image) = images.get(&strip.image) else {
+            continue;
+        };
+        let size = image.size();
+        let columns = ((size.x / frame_width) as usize).max(1);
+        let atlas = texture_atlases.add(TextureAtlas::from_grid(
+            strip.image.clone(),
+            Vec2::new(size.x / columns as f32, size.y),
+            columns,
+            1,
+            None,
+            None,
+        ));
+        strip.clip = Some(Clip {
+            atlas,
+            frames: (0..columns)
+                .map(|index| ClipFrame {
+                    index,
+                    duration: STRIP_FRAME_DURATION,
+                    events: Vec::new(),
+                    hitbox: None,
+                    hurtbox: None,
+                })
+                .collect(),
+            ..default()
+        });
+    }
+}
+
+fn show(
+    clip: &Clip,
+    cursor: usize,
+    animation: &mut Animation,
+    sprite: &mut TextureAtlasSprite,
+    atlas: &mut Handle<TextureAtlas>,
+) {
+    let speed = animation.speed;
+    *animation = Animation::from_clip(clip);
+    animation.speed = speed;
+    animation.set_cursor(cursor);
+    *atlas = clip.atlas.clone();
+    sprite.anchor = match clip.anchor {
+        Some(anchor) => Anchor::Custom(anchor),
+        None => Anchor::Center,
+    };
+    if let Some(index) = animation.frame() {
+        sprite.index = index;
+    }
+}
+
+fn select_entry(
+    keyboard_input: Res<Input<KeyCode>>,
+    catalog: Res<Catalog>,
+    animation_sheets: Res<Assets<AnimationSheet>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    mut sheet_events: EventReader<AssetEvent<AnimationSheet>>,
+    mut viewer: ResMut<Viewer>,
+    mut preview_query: Query<
+        (
+            &mut Animation,
+            &mut TextureAtlasSprite,
+            &mut Handle<TextureAtlas>,
+        ),
+        With<Preview>,
+    >,
+) {
+    let entries = entries(&catalog, &animation_sheets, &texture_atlases);
+    if entries.is_empty() {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        viewer.selected = (viewer.selected + 1) % entries.len();
+    }
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        viewer.selected = (viewer.selected + entries.len() - 1) % entries.len();
+    }
+    viewer.selected = viewer.selected.min(entries.len() - 1);
+
+    // sheets edited on disk or from the timing keys are shown again from the same frame
+    let sheet_modified = sheet_events
+        .iter()
+        .any(|event| matches!(event, AssetEvent::Modified { .. }));
+    let entry = &entries[viewer.selected];
+    let selection_changed = viewer.shown.as_ref() != Some(&entry.label);
+    if !selection_changed && !sheet_modified {
+        return;
+    }
+
+    let (mut animation, mut sprite, mut atlas) = preview_query.single_mut();
+    let cursor = match selection_changed {
+        true => 0,
+        false => animation.cursor(),
+    };
+    show(&entry.clip, cursor, &mut animation, &mut sprite, &mut atlas);
+    viewer.shown = Some(entry.label.clone());
+}
+
+fn control_playback(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut viewer: ResMut<Viewer>,
+    mut time_scale: ResMut<AnimationTimeScale>,
+    mut preview_query: Query<(&mut Animation, &mut TextureAtlasSprite), With<Preview>>,
+) {
+    let (mut animation, mut sprite) = preview_query.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        viewer.paused = !viewer.paused;
+        time_scale.0 = match viewer.paused {
+            true => 0.0,
+            false => 1.0,
+        };
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Plus, KeyCode::NumpadAdd, KeyCode::Equals]) {
+        animation.speed += SPEED_STEP;
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        animation.speed = (animation.speed - SPEED_STEP).max(SPEED_STEP);
+    }
+
+    let frame_count = animation.frame_count();
+    if frame_count == 0 {
+        return;
+    }
+    let cursor = animation.cursor();
+    let stepped = if keyboard_input.just_pressed(KeyCode::Right) {
+        Some((cursor + 1) % frame_count)
+    } else if keyboard_input.just_pressed(KeyCode::Left) {
+        Some((cursor + frame_count - 1) % frame_count)
+    } else if keyboard_input.just_pressed(KeyCode::Return) {
+        Some(0)
+    } else {
+        None
+    };
+    if let Some(cursor) = stepped {
+        animation.set_cursor(cursor);
+        if let Some(index) = animation.frame() {
+            sprite.index = index;
+        }
+    }
+}
+
+fn edit_timing(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut catalog: ResMut<Catalog>,
+    mut animation_sheets: ResMut<Assets<AnimationSheet>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    viewer: Res<Viewer>,
+    mut preview_query: Query<
+        (
+            &mut Animation,
+            &mut TextureAtlasSprite,
+            &mut Handle<TextureAtlas>,
+        ),
+        With<Preview>,
+    >,
+) {
+    let change = if keyboard_input.just_pressed(KeyCode::BracketRight) {
+        DURATION_STEP
+    } else if keyboard_input.just_pressed(KeyCode::BracketLeft) {
+        -DURATION_STEP
+    } else {
+        return;
+    };
+
+    let entries = entries(&catalog, &animation_sheets, &texture_atlases);
+    let Some(entry) = entries.get(viewer.selected) else {
+        return;
+    };
+    let (mut animation, mut sprite, mut atlas) = preview_query.single_mut();
+    let cursor = animation.cursor();
+
+    let clip = match &entry.source {
+        EntrySource::Sheet(handle, name) => animation_sheets
+            .get_mut(handle)
+            .and_then(|sheet| sheet.clips.get_mut(name)),
+        EntrySource::Strip(index) => catalog.strips[*index].clip.as_mut(),
+    };
+    let Some(clip) = clip else {
+        return;
+    };
+    let Some(frame) = clip.frames.get_mut(cursor) else {
+        return;
+    };
+    frame.duration = (frame.duration + change).max(DURATION_STEP);
+
+    // printed the way sheets declare them, ready to be copied back
+    let durations: Vec<String> = clip
+        .frames
+        .iter()
+        .enumerate()
+        .map(|(position, frame)| format!("{}: {:.2}", position, frame.duration))
+        .collect();
+    info!("{} durations: {{{}}}", entry.label, durations.join(", "));
+
+    show(clip, cursor, &mut animation, &mut sprite, &mut atlas);
+}
+
+fn frame_box_transform(frame_box: Option<&FrameBox>, transform: &mut Transform) -> Visibility {
+    let Some(frame_box) = frame_box else {
+        return Visibility::Hidden;
+    };
+    transform.translation.x = frame_box.offset.x * SCALE;
+    transform.translation.y = frame_box.offset.y * SCALE;
+    transform.scale = (frame_box.half_size * 2.0 * SCALE).extend(1.0);
+    Visibility::Inherited
+}
+
+fn update_overlays(
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    preview_query: Query<(&Animation, &TextureAtlasSprite, &Handle<TextureAtlas>), With<Preview>>,
+    mut overlay_query: Query<(&Overlay, &mut Transform, &mut Visibility)>,
+) {
+    let (animation, sprite, atlas) = preview_query.single();
+    let frame_size = texture_atlases
+        .get(atlas)
+        .and_then(|texture_atlas| texture_atlas.textures.get(sprite.index))
+        .map(|rect| rect.size())
+        .unwrap_or_default();
+    let frame = animation.current();
+
+    for (overlay, mut transform, mut visibility) in overlay_query.iter_mut() {
+        // sprites are 1x1 and scaled to the size they show
+        *visibility = match overlay {
+            Overlay::FrameBounds => {
+                // the anchor is the point of the frame sitting on the entity position
+                let center = -sprite.anchor.as_vec() * frame_size * SCALE;
+                transform.translation.x = center.x;
+                transform.translation.y = center.y;
+                transform.scale = (frame_size * SCALE).extend(1.0);
+                Visibility::Inherited
+            }
+            Overlay::PivotHorizontal => {
+                transform.scale = Vec3::new(4.0 * SCALE, 1.0, 1.0);
+                Visibility::Inherited
+            }
+            Overlay::PivotVertical => {
+                transform.scale = Vec3::new(1.0, 4.0 * SCALE, 1.0);
+                Visibility::Inherited
+            }
+            Overlay::Hitbox => frame_box_transform(
+                frame.and_then(|frame| frame.hitbox.as_ref()),
+                &mut transform,
+            ),
+            Overlay::Hurtbox => frame_box_transform(
+                frame.and_then(|frame| frame.hurtbox.as_ref()),
+                &mut transform,
+            ),
+        };
+    }
+}
+
+fn update_info(
+    catalog: Res<Catalog>,
+    animation_sheets: Res<Assets<AnimationSheet>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    viewer: Res<Viewer>,
+    preview_query: Query<&Animation, With<Preview>>,
+    mut text_query: Query<&mut Text, With<InfoText>>,
+) {
+    let animation = preview_query.single();
+    let entries = entries(&catalog, &animation_sheets, &texture_atlases);
+
+    let mut lines = Vec::new();
+    let first = viewer.selected.saturating_sub(LISTED_ENTRIES / 2);
+    for (index, entry) in entries.iter().enumerate().skip(first).take(LISTED_ENTRIES) {
+        let marker = match index == viewer.selected {
+            true => ">",
+            false => " ",
+        };
+        lines.push(format!("{} {}", marker, entry.label));
+    }
+    lines.push(String::new());
+    if let Some(frame) = animation.current() {
+        lines.push(format!(
+            "frame {}/{}  atlas index {}  duration {:.2}s",
+            animation.cursor() + 1,
+            animation.frame_count(),
+            frame.index,
+            frame.duration
+        ));
+        if !frame.events.is_empty() {
+            lines.push(format!("events {}", frame.events.join(" ")));
+        }
+    }
+    lines.push(format!(
+        "speed x{:.2}{}",
+        animation.speed,
+        match viewer.paused {
+            true => "  paused",
+            false => "",
+        }
+    ));
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
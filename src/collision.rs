@@ -1,5 +1,10 @@
+mod frame_box;
 mod groups;
 mod plugin;
 
+pub use frame_box::*;
 pub use groups::*;
 pub use plugin::*;
+
+#[cfg(test)]
+mod tests;
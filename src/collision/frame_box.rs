@@ -0,0 +1,203 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    animation::{animate, Animation, AnimationEvent, FrameBox},
+    kinematics::Orientation,
+    GameSet,
+};
+
+use super::groups::{FRAME_HITBOX_GROUP, FRAME_HURTBOX_GROUP};
+
+/// Keeps the hitbox and hurtbox sensors of animated entities in sync with the frame shown.
+pub struct FrameBoxPlugin;
+
+impl Plugin for FrameBoxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Hit>()
+            .add_event::<AnimationEvent>()
+            .add_system(detect_hits.in_set(GameSet::BeforeUpdate))
+            .add_system(
+                track_hit_windows
+                    .in_set(GameSet::Render)
+                    .after(animate)
+                    .before(update_frame_boxes),
+            )
+            .add_system(update_frame_boxes.in_set(GameSet::Render).after(animate));
+    }
+}
+
+/// Sent once per swing when a hitbox of `attacker` overlaps a hurtbox of `target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    pub attacker: Entity,
+    pub target: Entity,
+}
+
+// animation events opening and closing a hit window
+const HIT_START: &str = "hit_start";
+const HIT_END: &str = "hit_end";
+
+/// Keeps the hitboxes of the frames out until a `hit_start` animation event and removes them
+/// again on `hit_end`, so the damage follows the swing drawn rather than the whole clip.
+/// Entities without it use the hitbox of every frame.
+#[derive(Component, Default)]
+pub struct HitWindow {
+    open: bool,
+}
+
+impl HitWindow {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameBoxKind {
+    Hitbox,
+    Hurtbox,
+}
+
+#[derive(Component)]
+pub struct FrameBoxSensor {
+    pub kind: FrameBoxKind,
+    frame_box: FrameBox,
+    direction: f32,
+}
+
+// targets already hit by a hitbox, cleared when the hitbox goes away with its entity
+#[derive(Component, Default)]
+struct HitTargets(HashSet<Entity>);
+
+fn frame_box_transform(frame_box: &FrameBox, direction: f32) -> Transform {
+    Transform::from_xyz(frame_box.offset.x * direction, frame_box.offset.y, 0.0)
+}
+
+fn track_hit_windows(
+    mut animation_events: EventReader<AnimationEvent>,
+    mut window_query: Query<&mut HitWindow>,
+) {
+    for event in animation_events.iter() {
+        let Ok(mut window) = window_query.get_mut(event.entity) else {
+            continue;
+        };
+        let open = match event.name.as_str() {
+            HIT_START => true,
+            HIT_END => false,
+            _ => continue,
+        };
+        if window.open != open {
+            window.open = open;
+        }
+    }
+}
+
+fn update_frame_boxes(
+    mut commands: Commands,
+    animation_query: Query<
+        (
+            Entity,
+            &Animation,
+            Option<&HitWindow>,
+            Option<&Orientation>,
+            Option<&Children>,
+        ),
+        Or<(Changed<Animation>, Changed<HitWindow>, Changed<Orientation>)>,
+    >,
+    mut sensor_query: Query<(&mut FrameBoxSensor, &mut Collider, &mut Transform)>,
+) {
+    for (entity, animation, hit_window, orientation, children) in animation_query.iter() {
+        // sensors have no GlobalTransform so they are mirrored by hand, like flip_sensor does
+        let direction = match orientation {
+            Some(Orientation::Left) => -1.0,
+            _ => 1.0,
+        };
+
+        for kind in [FrameBoxKind::Hitbox, FrameBoxKind::Hurtbox] {
+            let frame_box = animation.current().and_then(|frame| match kind {
+                FrameBoxKind::Hitbox => frame
+                    .hitbox
+                    .filter(|_| hit_window.map_or(true, HitWindow::is_open)),
+                FrameBoxKind::Hurtbox => frame.hurtbox,
+            });
+            let sensor_entity = children.into_iter().flatten().copied().find(|&child| {
+                sensor_query
+                    .get(child)
+                    .map_or(false, |(sensor, ..)| sensor.kind == kind)
+            });
+
+            match (frame_box, sensor_entity) {
+                (Some(frame_box), Some(sensor_entity)) => {
+                    let (mut sensor, mut collider, mut transform) =
+                        sensor_query.get_mut(sensor_entity).unwrap();
+                    if sensor.frame_box != frame_box || sensor.direction != direction {
+                        *collider = Collider::cuboid(frame_box.half_size.x, frame_box.half_size.y);
+                        *transform = frame_box_transform(&frame_box, direction);
+                        sensor.frame_box = frame_box;
+                        sensor.direction = direction;
+                    }
+                }
+                (Some(frame_box), None) => {
+                    let mut sensor_commands = commands.spawn((
+                        Name::new(format!("{:?}", kind)),
+                        FrameBoxSensor {
+                            kind,
+                            frame_box,
+                            direction,
+                        },
+                        Collider::cuboid(frame_box.half_size.x, frame_box.half_size.y),
+                        frame_box_transform(&frame_box, direction),
+                        Sensor,
+                        ColliderScale::Absolute(Vec2::splat(1.0)),
+                        ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+                    ));
+                    match kind {
+                        FrameBoxKind::Hitbox => {
+                            sensor_commands.insert((FRAME_HITBOX_GROUP, HitTargets::default()))
+                        }
+                        FrameBoxKind::Hurtbox => sensor_commands.insert(FRAME_HURTBOX_GROUP),
+                    };
+                    let sensor_entity = sensor_commands.id();
+                    commands.entity(entity).add_child(sensor_entity);
+                }
+                (None, Some(sensor_entity)) => {
+                    commands.entity(sensor_entity).despawn_recursive();
+                }
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+fn detect_hits(
+    rapier_context: Res<RapierContext>,
+    mut hits: EventWriter<Hit>,
+    mut hitbox_query: Query<(Entity, &Parent, &mut HitTargets)>,
+    hurtbox_query: Query<(&Parent, &FrameBoxSensor)>,
+) {
+    for (hitbox, attacker, mut hit_targets) in hitbox_query.iter_mut() {
+        for (collider1, collider2, intersecting) in rapier_context.intersections_with(hitbox) {
+            let other = match collider1 == hitbox {
+                true => collider2,
+                false => collider1,
+            };
+            let Ok((target, sensor)) = hurtbox_query.get(other) else {
+                continue;
+            };
+            // characters cannot hurt themselves
+            if !intersecting
+                || sensor.kind != FrameBoxKind::Hurtbox
+                || target.get() == attacker.get()
+            {
+                continue;
+            }
+
+            if hit_targets.0.insert(target.get()) {
+                hits.send(Hit {
+                    attacker: attacker.get(),
+                    target: target.get(),
+                });
+            }
+        }
+    }
+}
@@ -13,20 +13,26 @@ mod groups {
 
     pub const ENEMY_COLLIDER: Group   = Group::GROUP_4;
     pub const GROUND_SENSOR: Group = Group::GROUP_5;
+
+    pub const CRATE: Group            = Group::GROUP_7;
+
+    pub const FRAME_HITBOX: Group     = Group::GROUP_8;
+    pub const FRAME_HURTBOX: Group    = Group::GROUP_9;
 }
 
 pub const GROUND_GROUP: CollisionGroups = CollisionGroups::new(
     groups::GROUND,
     groups::PLAYER_COLLIDER
         .union(groups::ENEMY_COLLIDER)
-        .union(groups::GROUND_SENSOR),
+        .union(groups::GROUND_SENSOR)
+        .union(groups::CRATE),
 );
 
 pub const PLAYER_COLLIDER_GROUP: CollisionGroups =
-    CollisionGroups::new(groups::PLAYER_COLLIDER, groups::GROUND);
+    CollisionGroups::new(groups::PLAYER_COLLIDER, groups::GROUND.union(groups::CRATE));
 
 pub const ENEMY_COLLIDER_GROUP: CollisionGroups =
-    CollisionGroups::new(groups::ENEMY_COLLIDER, groups::GROUND);
+    CollisionGroups::new(groups::ENEMY_COLLIDER, groups::GROUND.union(groups::CRATE));
 
 pub const PLAYER_HITBOX_GROUP: CollisionGroups =
     CollisionGroups::new(groups::PLAYER_HITBOX, groups::PLAYER_SENSOR);
@@ -35,4 +41,19 @@ pub const PLAYER_SENSOR_GROUP: CollisionGroups =
     CollisionGroups::new(groups::PLAYER_SENSOR, groups::PLAYER_HITBOX);
 
 pub const GROUND_SENSOR_GROUP: CollisionGroups =
-    CollisionGroups::new(groups::GROUND_SENSOR, groups::GROUND);
+    CollisionGroups::new(groups::GROUND_SENSOR, groups::GROUND.union(groups::CRATE));
+
+pub const CRATE_GROUP: CollisionGroups = CollisionGroups::new(
+    groups::CRATE,
+    groups::GROUND
+        .union(groups::PLAYER_COLLIDER)
+        .union(groups::ENEMY_COLLIDER)
+        .union(groups::GROUND_SENSOR)
+        .union(groups::CRATE),
+);
+
+pub const FRAME_HITBOX_GROUP: CollisionGroups =
+    CollisionGroups::new(groups::FRAME_HITBOX, groups::FRAME_HURTBOX);
+
+pub const FRAME_HURTBOX_GROUP: CollisionGroups =
+    CollisionGroups::new(groups::FRAME_HURTBOX, groups::FRAME_HITBOX);
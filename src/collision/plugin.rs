@@ -1,10 +1,15 @@
 use std::{fmt::Debug, hash::Hash, marker::PhantomData};
 
-use bevy::{prelude::*, utils::HashSet};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use bevy_rapier2d::prelude::*;
 
 use crate::{kinematics::Orientation, GameSet};
 
+use super::frame_box::FrameBoxPlugin;
+
 pub trait CollisionSensorComponent =
     CollisionSensor + Component + Debug + Copy + Eq + PartialEq + Hash + Send + Sync + 'static;
 
@@ -22,7 +27,13 @@ impl<T: CollisionSensorComponent> Default for CollisionPlugin<T> {
 
 impl<T: CollisionSensorComponent + Debug> Plugin for CollisionPlugin<T> {
     fn build(&self, app: &mut App) {
-        app.add_system(add_collision_sensor::<T>.in_set(GameSet::BeforeUpdate))
+        if !app.is_plugin_added::<FrameBoxPlugin>() {
+            app.add_plugin(FrameBoxPlugin);
+        }
+
+        app.add_event::<SensorEntered<T>>()
+            .add_event::<SensorExited<T>>()
+            .add_system(add_collision_sensor::<T>.in_set(GameSet::BeforeUpdate))
             .add_system(
                 flip_sensor::<T>
                     .in_set(GameSet::BeforeUpdate)
@@ -38,6 +49,10 @@ impl<T: CollisionSensorComponent + Debug> Plugin for CollisionPlugin<T> {
 
 pub trait CollisionSensor: Sized {
     fn get_sensors() -> Vec<(Self, Collider, Vec2, CollisionGroups)>;
+
+    fn get_crouching_sensor(&self) -> Option<(Collider, Vec2)> {
+        None
+    }
 }
 
 #[derive(Bundle)]
@@ -55,19 +70,42 @@ impl<T: CollisionSensorComponent> Default for CollisionBundle<T> {
     }
 }
 
+/// What the sensors of an entity touch: whether each sensor touches anything in `collisions`,
+/// and which entities it overlaps. An overlapped collider attached to a rigid body is recorded as
+/// that body, so a hitbox sensor child stands for its character.
 #[derive(Component)]
 pub struct Collision<T> {
     pub collisions: HashSet<T>,
+    overlaps: HashMap<T, HashSet<Entity>>,
 }
 
 impl<T: CollisionSensorComponent> Default for Collision<T> {
     fn default() -> Self {
         Self {
             collisions: HashSet::new(),
+            overlaps: HashMap::new(),
         }
     }
 }
 
+/// Sent when the `sensor` of `entity` starts overlapping `other`. Like in [`Collision`], `other`
+/// is the rigid body when the overlapped collider is attached to one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorEntered<T> {
+    pub entity: Entity,
+    pub sensor: T,
+    pub other: Entity,
+}
+
+/// Sent when the `sensor` of `entity` stops overlapping `other`, the rigid body when the
+/// collider is attached to one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorExited<T> {
+    pub entity: Entity,
+    pub sensor: T,
+    pub other: Entity,
+}
+
 impl<T: Eq + PartialEq + Hash> Collision<T> {
     pub fn add(&mut self, sensor: T) {
         self.collisions.insert(sensor);
@@ -80,6 +118,23 @@ impl<T: Eq + PartialEq + Hash> Collision<T> {
     pub fn get(&self, sensor: &T) -> bool {
         self.collisions.contains(sensor)
     }
+
+    /// Entities `sensor` overlaps, in no particular order.
+    pub fn overlapping(&self, sensor: &T) -> impl Iterator<Item = Entity> + '_ {
+        self.overlaps.get(sensor).into_iter().flatten().copied()
+    }
+
+    pub fn is_overlapping(&self, sensor: &T, entity: Entity) -> bool {
+        self.overlaps
+            .get(sensor)
+            .map_or(false, |overlaps| overlaps.contains(&entity))
+    }
+
+    /// First entity overlapped by `sensor` matching `predicate`, typically a `Query::contains`
+    /// to find the player in an attack zone or the tile under the feet.
+    pub fn find(&self, sensor: &T, mut predicate: impl FnMut(Entity) -> bool) -> Option<Entity> {
+        self.overlapping(sensor).find(|&entity| predicate(entity))
+    }
 }
 
 impl<T: CollisionSensor> Collision<T> {
@@ -123,28 +178,61 @@ pub fn add_collision_sensor<T: CollisionSensorComponent>(
 }
 
 fn detect_collision<T: CollisionSensorComponent + Debug>(
-    mut collision_query: Query<(&Children, &mut Collision<T>)>,
+    mut collision_query: Query<(Entity, &Children, &mut Collision<T>)>,
     sensor_query: Query<(Entity, &T)>,
     rapier_context: Res<RapierContext>,
+    mut entered_events: EventWriter<SensorEntered<T>>,
+    mut exited_events: EventWriter<SensorExited<T>>,
+    // reused across sensors and frames, it only leaves when the overlaps changed
+    mut overlapping: Local<HashSet<Entity>>,
 ) {
-    for (children, mut collision) in collision_query.iter_mut() {
+    for (owner, children, mut collision) in collision_query.iter_mut() {
         for &child in children.iter() {
             if let Ok((entity, sensor)) = sensor_query.get(child) {
-                let colliding = rapier_context
-                    .intersections_with(entity)
-                    .any(|(_, _, intersecting)| intersecting);
-                match colliding {
-                    true => {
-                        if !collision.get(sensor) {
-                            collision.add(*sensor);
-                        }
-                    }
-                    false => {
-                        if collision.get(sensor) {
-                            collision.remove(sensor);
-                        }
-                    }
+                overlapping.clear();
+                overlapping.extend(
+                    rapier_context
+                        .intersections_with(entity)
+                        .filter(|(_, _, intersecting)| *intersecting)
+                        .map(|(collider1, collider2, _)| {
+                            let other = match collider1 == entity {
+                                true => collider2,
+                                false => collider1,
+                            };
+                            // several colliders of the same body count once
+                            rapier_context.collider_parent(other).unwrap_or(other)
+                        }),
+                );
+
+                // only read until then, so Changed<Collision<T>> fires on real changes
+                let empty = HashSet::new();
+                let previous = collision.overlaps.get(sensor).unwrap_or(&empty);
+                if *previous == *overlapping {
+                    continue;
+                }
+
+                for &other in overlapping.difference(previous) {
+                    entered_events.send(SensorEntered {
+                        entity: owner,
+                        sensor: *sensor,
+                        other,
+                    });
+                }
+                for &other in previous.difference(&overlapping) {
+                    exited_events.send(SensorExited {
+                        entity: owner,
+                        sensor: *sensor,
+                        other,
+                    });
+                }
+
+                match overlapping.is_empty() {
+                    true => collision.remove(sensor),
+                    false => collision.add(*sensor),
                 }
+                collision
+                    .overlaps
+                    .insert(*sensor, std::mem::take(&mut *overlapping));
             }
         }
     }
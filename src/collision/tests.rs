@@ -0,0 +1,226 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    animation::{Animation, AnimationEvent, Clip, ClipFrame, FrameBox},
+    test_support::FRAME,
+    GameSetsPlugin,
+};
+
+use super::{
+    Collision, CollisionBundle, CollisionPlugin, CollisionSensor, FrameBoxKind, FrameBoxSensor,
+    HitWindow, SensorEntered, SensorExited, GROUND_GROUP, GROUND_SENSOR_GROUP,
+};
+
+#[derive(Component, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum TestSensor {
+    Feet,
+}
+
+impl CollisionSensor for TestSensor {
+    fn get_sensors() -> Vec<(Self, Collider, Vec2, CollisionGroups)> {
+        vec![(
+            Self::Feet,
+            Collider::cuboid(4.0, 1.0),
+            Vec2::new(0.0, -10.0),
+            GROUND_SENSOR_GROUP,
+        )]
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+        .insert_resource(RapierConfiguration {
+            timestep_mode: TimestepMode::Fixed {
+                dt: FRAME.as_secs_f32(),
+                substeps: 1,
+            },
+            ..default()
+        })
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .add_plugin(GameSetsPlugin)
+        .add_plugin(CollisionPlugin::<TestSensor>::default());
+    app
+}
+
+fn ground(app: &mut App, position: Vec2) -> Entity {
+    app.world
+        .spawn((
+            Collider::cuboid(8.0, 8.0),
+            GROUND_GROUP,
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        ))
+        .id()
+}
+
+#[test]
+fn sensors_tell_what_they_start_and_stop_overlapping() {
+    let mut app = app();
+    let owner = app
+        .world
+        .spawn((
+            RigidBody::KinematicPositionBased,
+            TransformBundle::default(),
+            CollisionBundle::<TestSensor> {
+                collider: Collider::cuboid(4.0, 9.0),
+                ..default()
+            },
+        ))
+        .id();
+    let floor = ground(&mut app, Vec2::new(0.0, -18.0));
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let entered: Vec<_> = app
+        .world
+        .resource_mut::<Events<SensorEntered<TestSensor>>>()
+        .drain()
+        .collect();
+    assert_eq!(
+        entered,
+        [SensorEntered {
+            entity: owner,
+            sensor: TestSensor::Feet,
+            other: floor,
+        }]
+    );
+    assert!(app
+        .world
+        .get::<Collision<TestSensor>>(owner)
+        .unwrap()
+        .get(&TestSensor::Feet));
+
+    app.world.entity_mut(floor).despawn();
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let exited: Vec<_> = app
+        .world
+        .resource_mut::<Events<SensorExited<TestSensor>>>()
+        .drain()
+        .collect();
+    assert_eq!(
+        exited,
+        [SensorExited {
+            entity: owner,
+            sensor: TestSensor::Feet,
+            other: floor,
+        }]
+    );
+    assert!(!app
+        .world
+        .get::<Collision<TestSensor>>(owner)
+        .unwrap()
+        .get(&TestSensor::Feet));
+}
+
+#[test]
+fn sensors_list_every_entity_they_overlap() {
+    let mut app = app();
+    let owner = app
+        .world
+        .spawn((
+            RigidBody::KinematicPositionBased,
+            TransformBundle::default(),
+            CollisionBundle::<TestSensor> {
+                collider: Collider::cuboid(4.0, 9.0),
+                ..default()
+            },
+        ))
+        .id();
+    let left = ground(&mut app, Vec2::new(-6.0, -18.0));
+    let right = ground(&mut app, Vec2::new(6.0, -18.0));
+    // a collider attached to a body stands for that body
+    let platform = app
+        .world
+        .spawn((
+            RigidBody::Fixed,
+            TransformBundle::from_transform(Transform::from_xyz(40.0, -18.0, 0.0)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Collider::cuboid(8.0, 8.0),
+                GROUND_GROUP,
+                TransformBundle::from_transform(Transform::from_xyz(-40.0, 0.0, 0.0)),
+            ));
+        })
+        .id();
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let collision = app.world.get::<Collision<TestSensor>>(owner).unwrap();
+    let mut overlapping: Vec<_> = collision.overlapping(&TestSensor::Feet).collect();
+    overlapping.sort();
+    let mut expected = vec![left, right, platform];
+    expected.sort();
+    assert_eq!(overlapping, expected);
+    assert!(collision.is_overlapping(&TestSensor::Feet, platform));
+    assert_eq!(
+        collision.find(&TestSensor::Feet, |entity| entity == right),
+        Some(right)
+    );
+    assert_eq!(
+        collision.find(&TestSensor::Feet, |entity| entity == owner),
+        None
+    );
+
+    // events name the body as well
+    let mut entered: Vec<_> = app
+        .world
+        .resource_mut::<Events<SensorEntered<TestSensor>>>()
+        .drain()
+        .map(|event| event.other)
+        .collect();
+    entered.sort();
+    assert_eq!(entered, expected);
+}
+
+#[test]
+fn hitboxes_only_exist_within_the_hit_window() {
+    let mut app = app();
+    let hitbox = FrameBox {
+        offset: Vec2::new(15.0, 0.0),
+        half_size: Vec2::new(8.0, 18.0),
+    };
+    let attacker = app
+        .world
+        .spawn((
+            Animation::from_clip(&Clip {
+                frames: vec![ClipFrame {
+                    index: 0,
+                    duration: 0.1,
+                    events: Vec::new(),
+                    hitbox: Some(hitbox),
+                    hurtbox: None,
+                }],
+                ..default()
+            }),
+            HitWindow::default(),
+            TransformBundle::default(),
+        ))
+        .id();
+    let has_hitbox = |app: &mut App| {
+        let mut sensor_query = app.world.query::<(&Parent, &FrameBoxSensor)>();
+        sensor_query
+            .iter(&app.world)
+            .any(|(parent, sensor)| parent.get() == attacker && sensor.kind == FrameBoxKind::Hitbox)
+    };
+    app.update();
+    assert!(!has_hitbox(&mut app));
+
+    for (name, open) in [("hit_start", true), ("hit_end", false)] {
+        app.world.send_event(AnimationEvent {
+            entity: attacker,
+            name: name.to_string(),
+        });
+        app.update();
+        assert_eq!(has_hitbox(&mut app), open);
+    }
+}
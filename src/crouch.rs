@@ -0,0 +1,103 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::{Collision, CollisionSensorComponent},
+    GameSet,
+};
+
+pub struct CrouchPlugin<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T: CollisionSensorComponent> Default for CrouchPlugin<T> {
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: CollisionSensorComponent> Plugin for CrouchPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_system(resize_crouching_collider::<T>.in_set(GameSet::AfterUpdate))
+            .add_system(resize_crouching_sensors::<T>.in_set(GameSet::AfterUpdate));
+    }
+}
+
+/// Capsule body that can shrink from the top while keeping its feet in place.
+#[derive(Component)]
+pub struct Crouch {
+    pub crouching: bool,
+    half_height: f32,
+    crouching_half_height: f32,
+    radius: f32,
+}
+
+impl Crouch {
+    pub fn new(half_height: f32, crouching_half_height: f32, radius: f32) -> Self {
+        Self {
+            crouching: false,
+            half_height,
+            crouching_half_height,
+            radius,
+        }
+    }
+
+    /// Height of the feet below the character origin, the same crouching or not.
+    pub fn bottom(&self) -> f32 {
+        -(self.half_height + self.radius)
+    }
+
+    pub fn collider(&self) -> Collider {
+        match self.crouching {
+            true => {
+                // the collider lives on the character entity itself, so the shorter capsule is
+                // pushed down inside a compound to keep the bottom of the capsule where it was
+                let offset = self.half_height - self.crouching_half_height;
+                Collider::compound(vec![(
+                    Vec2::new(0.0, -offset),
+                    0.0,
+                    Collider::capsule_y(self.crouching_half_height, self.radius),
+                )])
+            }
+            false => Collider::capsule_y(self.half_height, self.radius),
+        }
+    }
+}
+
+fn resize_crouching_collider<T: CollisionSensorComponent>(
+    mut crouch_query: Query<(&Crouch, &mut Collider), (With<Collision<T>>, Changed<Crouch>)>,
+) {
+    // rapier scales and swaps the shape of any changed collider on its own
+    for (crouch, mut collider) in crouch_query.iter_mut() {
+        *collider = crouch.collider();
+    }
+}
+
+fn resize_crouching_sensors<T: CollisionSensorComponent>(
+    crouch_query: Query<(&Crouch, &Children), (With<Collision<T>>, Changed<Crouch>)>,
+    mut sensor_query: Query<(&T, &mut Collider, &mut Transform)>,
+) {
+    for (crouch, children) in crouch_query.iter() {
+        for &child in children.iter() {
+            if let Ok((sensor, mut collider, mut transform)) = sensor_query.get_mut(child) {
+                let shape = match crouch.crouching {
+                    true => sensor.get_crouching_sensor(),
+                    false => T::get_sensors()
+                        .into_iter()
+                        .find(|(standing_sensor, ..)| standing_sensor == sensor)
+                        .map(|(_, collider, relative_position, _)| (collider, relative_position)),
+                };
+
+                if let Some((new_collider, relative_position)) = shape {
+                    *collider = new_collider;
+                    // the horizontal offset is owned by flip_sensor, only the height changes here
+                    transform.translation.y = relative_position.y;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::{collision::Hit, GameSet};
+
+// every hit costs the same for now
+const HIT_DAMAGE: u32 = 1;
+
+/// Takes health away from hit targets and tells when it runs out.
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HealthDepleted>()
+            .add_system(damage_hit_targets.in_set(GameSet::Update));
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_depleted(&self) -> bool {
+        self.current == 0
+    }
+
+    /// Takes `damage` away, returns whether this is what depleted the health.
+    pub fn take(&mut self, damage: u32) -> bool {
+        if self.is_depleted() {
+            return false;
+        }
+        self.current = self.current.saturating_sub(damage);
+        self.is_depleted()
+    }
+}
+
+/// Sent once when the [`Health`] of `entity` reaches zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthDepleted {
+    pub entity: Entity,
+}
+
+fn damage_hit_targets(
+    mut hits: EventReader<Hit>,
+    mut depleted: EventWriter<HealthDepleted>,
+    mut health_query: Query<&mut Health>,
+) {
+    for hit in hits.iter() {
+        if let Ok(mut health) = health_query.get_mut(hit.target) {
+            if health.take(HIT_DAMAGE) {
+                depleted.send(HealthDepleted { entity: hit.target });
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::{
+    animation::{apply_visual_transforms, VisualTransform},
+    collision::Hit,
+    kinematics::{cancel_gravity, Gravity, Velocity},
+    sprite_effects::Easing,
+    GameSet,
+};
+
+#[cfg(test)]
+mod tests;
+
+const TAKE_OFF_STRETCH: f32 = 0.2;
+// kinematic velocities are in pixels per frame, the squash grows with the fall speed
+const LANDING_SQUASH_PER_SPEED: f32 = 0.06;
+const MAX_LANDING_SQUASH: f32 = 0.35;
+const HIT_SQUASH: f32 = 0.15;
+
+pub struct JuicePlugin;
+
+impl Plugin for JuicePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(squash_hit_targets.in_set(GameSet::Update))
+            .add_system(stretch_take_offs.in_set(GameSet::AfterUpdate))
+            .add_system(
+                squash_landings
+                    .in_set(GameSet::AfterUpdate)
+                    .before(cancel_gravity),
+            )
+            .add_system(
+                apply_juice
+                    .in_set(GameSet::Render)
+                    .before(apply_visual_transforms),
+            );
+    }
+}
+
+/// Short squash and stretch of the sprite, played through its [`VisualTransform`] so the
+/// collider keeps its shape.
+#[derive(Component, Debug, Clone)]
+pub struct Juice {
+    pub duration: f32,
+    pub easing: Easing,
+    deformation: Vec2,
+    timer: Timer,
+}
+
+impl Default for Juice {
+    fn default() -> Self {
+        Self::new(0.2)
+    }
+}
+
+impl Juice {
+    pub fn new(duration: f32) -> Self {
+        let mut timer = Timer::from_seconds(duration, TimerMode::Once);
+        // nothing to play until the first deformation
+        timer.tick(timer.duration());
+        Self {
+            duration,
+            easing: Easing::EaseOut,
+            deformation: Vec2::ZERO,
+            timer,
+        }
+    }
+
+    /// Wider and shorter, `amount` being the share of the height lost.
+    pub fn squash(&mut self, amount: f32) {
+        self.deform(Vec2::new(amount, -amount));
+    }
+
+    /// Taller and thinner, `amount` being the share of the height gained.
+    pub fn stretch(&mut self, amount: f32) {
+        self.deform(Vec2::new(-amount, amount));
+    }
+
+    // the last deformation wins, they are too short to be worth blending
+    fn deform(&mut self, deformation: Vec2) {
+        self.deformation = deformation;
+        self.timer = Timer::from_seconds(self.duration, TimerMode::Once);
+    }
+
+    pub fn tick(&mut self, delta: std::time::Duration) {
+        self.timer.tick(delta);
+    }
+
+    pub fn scale(&self) -> Vec2 {
+        if self.timer.finished() {
+            return Vec2::ONE;
+        }
+        Vec2::ONE + self.deformation * self.easing.strength(self.timer.percent())
+    }
+}
+
+// gravity comes back as soon as the ground is left, a jump is the only way to leave it going up
+fn stretch_take_offs(mut juice_query: Query<(&mut Juice, &Velocity), Added<Gravity>>) {
+    for (mut juice, velocity) in juice_query.iter_mut() {
+        if velocity.y > 0.0 {
+            juice.stretch(TAKE_OFF_STRETCH);
+        }
+    }
+}
+
+// gravity is removed on touchdown, the fall speed is still there until cancel_gravity runs
+fn squash_landings(
+    mut removed_gravity: RemovedComponents<Gravity>,
+    mut juice_query: Query<(&mut Juice, &Velocity), Without<Gravity>>,
+) {
+    for entity in removed_gravity.iter() {
+        let Ok((mut juice, velocity)) = juice_query.get_mut(entity) else {
+            continue;
+        };
+        let impact = -velocity.y * LANDING_SQUASH_PER_SPEED;
+        if impact > 0.0 {
+            juice.squash(impact.min(MAX_LANDING_SQUASH));
+        }
+    }
+}
+
+fn squash_hit_targets(mut hits: EventReader<Hit>, mut juice_query: Query<&mut Juice>) {
+    for hit in hits.iter() {
+        if let Ok(mut juice) = juice_query.get_mut(hit.target) {
+            juice.squash(HIT_SQUASH);
+        }
+    }
+}
+
+fn apply_juice(time: Res<Time>, mut juice_query: Query<(&mut Juice, &mut VisualTransform)>) {
+    for (mut juice, mut visual) in juice_query.iter_mut() {
+        juice.tick(time.delta());
+        let scale = juice.scale();
+        if visual.scale != scale {
+            visual.scale = scale;
+        }
+    }
+}
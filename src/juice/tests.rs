@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::sprite_effects::Easing;
+
+use super::Juice;
+
+#[test]
+fn deformations_ease_back_to_the_drawn_size() {
+    let mut juice = Juice::new(0.5);
+    juice.easing = Easing::Linear;
+    assert_eq!(juice.scale(), Vec2::ONE);
+
+    juice.squash(0.25);
+    assert_eq!(juice.scale(), Vec2::new(1.25, 0.75));
+
+    juice.tick(Duration::from_secs_f32(0.25));
+    assert_eq!(juice.scale(), Vec2::new(1.125, 0.875));
+
+    juice.tick(Duration::from_secs_f32(0.25));
+    assert_eq!(juice.scale(), Vec2::ONE);
+}
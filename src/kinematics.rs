@@ -32,6 +32,9 @@ pub struct Velocity {
     pub y: f32,
 }
 
+/// Direction a character faces. Only sprites with a
+/// [`VisualTransform`](crate::animation::VisualTransform) turn around with it, the `Transform` is
+/// left to the simulation.
 #[derive(Component, Default, Eq, PartialEq, Debug)]
 pub enum Orientation {
     #[default]
@@ -48,7 +51,7 @@ fn apply_gravity(time: Res<Time>, mut gravity_query: Query<&mut Velocity, With<G
     }
 }
 
-fn cancel_gravity(
+pub fn cancel_gravity(
     mut removed_gravity: RemovedComponents<Gravity>,
     mut velocity_query: Query<&mut Velocity>,
 ) {
@@ -60,20 +63,15 @@ fn cancel_gravity(
 }
 
 fn update_character_orientations(
-    mut character_query: Query<
-        (&mut Orientation, &mut Transform, &Velocity),
-        With<KinematicCharacterController>,
-    >,
+    mut character_query: Query<(&mut Orientation, &Velocity), With<KinematicCharacterController>>,
 ) {
-    for (mut facing_direction, mut transform, velocity) in character_query.iter_mut() {
+    for (mut facing_direction, velocity) in character_query.iter_mut() {
         if velocity.x != 0.0 {
             let new_direction = match velocity.x.is_sign_positive() {
                 true => Orientation::Right,
                 false => Orientation::Left,
             };
 
-            transform.scale.x = velocity.x.signum();
-
             facing_direction.set_if_neq(new_direction);
         }
     }
@@ -5,4 +5,8 @@ mod sensor;
 mod state;
 mod systems;
 
+#[cfg(test)]
+mod tests;
+
+pub use components::KnightDied;
 pub use plugin::KnightPlugin;
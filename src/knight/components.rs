@@ -5,3 +5,16 @@ pub struct Knight {
     pub move_speed: f32,
     pub direction: f32,
 }
+
+/// A dead knight, fading out once its death clip is over.
+#[derive(Component)]
+pub struct Corpse {
+    pub fade: Timer,
+}
+
+/// Sent when a knight dies, before its corpse fades out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KnightDied {
+    pub entity: Entity,
+    pub position: Vec2,
+}
@@ -1,29 +1,49 @@
 use bevy::prelude::*;
 
-use crate::{animation::AnimationPlugin, collision::CollisionPlugin, GameSet};
+use crate::{
+    animation::AnimationPlugin, collision::CollisionPlugin, crouch::CrouchPlugin,
+    health::HealthPlugin, sprite_effects::apply_sprite_effects, GameSet,
+};
 
 use super::{
-    animation::{load_knight_textures, KnightSpritesHandles},
+    components::KnightDied,
     sensor::KnightSensor,
-    state::{update_knight_state, KnightState},
-    systems::{handle_knight_collision_changes, init_knight, move_knight},
+    state::{knight_state_machine, update_knight_state, KnightState},
+    systems::{
+        decay_corpses, fade_corpses, handle_knight_collision_changes, init_knight, kill_knights,
+        move_knight,
+    },
 };
 
 pub struct KnightPlugin;
 
 impl Plugin for KnightPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<KnightSpritesHandles>()
-            .add_startup_system(load_knight_textures)
-            .add_startup_system(init_knight.after(load_knight_textures))
+        if !app.is_plugin_added::<HealthPlugin>() {
+            app.add_plugin(HealthPlugin);
+        }
+
+        app.add_event::<KnightDied>()
+            .insert_resource(knight_state_machine())
+            .add_startup_system(init_knight)
             .add_plugin(CollisionPlugin::<KnightSensor>::default())
-            .add_plugin(AnimationPlugin::<KnightState, KnightSpritesHandles>::default())
+            .add_plugin(CrouchPlugin::<KnightSensor>::default())
+            .add_plugin(AnimationPlugin::<KnightState>::new(
+                "knight/knight.anim.ron",
+            ))
             .add_system(handle_knight_collision_changes.in_set(GameSet::Update))
             .add_system(move_knight.in_set(GameSet::Update))
             .add_system(
                 update_knight_state
                     .in_set(GameSet::Update)
                     .after(move_knight),
+            )
+            .add_system(kill_knights.in_set(GameSet::AfterUpdate))
+            .add_system(decay_corpses.in_set(GameSet::AfterUpdate))
+            .add_system(
+                fade_corpses
+                    .in_set(GameSet::Render)
+                    .after(apply_sprite_effects),
             );
     }
 }
@@ -75,4 +75,12 @@ impl CollisionSensor for KnightSensor {
             ),
         ]
     }
+
+    fn get_crouching_sensor(&self) -> Option<(Collider, Vec2)> {
+        match self {
+            Self::Front => Some((Collider::cuboid(1.0, 6.0), Vec2::new(6.5, -5.0))),
+            Self::Back => Some((Collider::cuboid(1.0, 6.0), Vec2::new(-6.5, -5.0))),
+            _ => None,
+        }
+    }
 }
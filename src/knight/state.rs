@@ -1,39 +1,101 @@
 use bevy::prelude::*;
+use strum::{EnumIter, IntoStaticStr};
 
-use crate::{animation::Animation, collision::Collision};
+use crate::{
+    animation::Animation, collision::Collision, crouch::Crouch, player::Player,
+    state_machine::StateMachine,
+};
 
 use super::sensor::KnightSensor;
 
-#[derive(Component, Eq, PartialEq, Hash, Debug)]
+#[derive(Component, Clone, Copy, Eq, PartialEq, Hash, Debug, IntoStaticStr, EnumIter)]
 pub enum KnightState {
     Idle,
     Running,
     Attack,
+    Crouching,
+    CrouchWalking,
+    Dead,
+}
+
+impl KnightState {
+    pub fn is_crouching(&self) -> bool {
+        match self {
+            Self::Crouching | Self::CrouchWalking => true,
+            _ => false,
+        }
+    }
+}
+
+/// What the knight state machine decides on, gathered every frame.
+pub struct KnightConditions {
+    pub crouching: bool,
+    pub moving: bool,
+    pub player_in_reach: bool,
+    pub animation_finished: bool,
+}
+
+// the dead state is left out, it is driven by kill_knights
+pub fn knight_state_machine() -> StateMachine<KnightState, KnightConditions> {
+    StateMachine::default()
+        .lock(KnightState::Attack, |c| !c.animation_finished)
+        .from_any(KnightState::CrouchWalking, |c| c.crouching && c.moving)
+        .from_any(KnightState::Crouching, |c| c.crouching)
+        // a finished attack goes back to walking once before attacking again
+        .transition(
+            &[
+                KnightState::Idle,
+                KnightState::Running,
+                KnightState::Crouching,
+                KnightState::CrouchWalking,
+            ],
+            KnightState::Attack,
+            |c| c.player_in_reach,
+        )
+        .from_any(KnightState::Running, |c| c.moving)
+        .from_any(KnightState::Idle, |_| true)
 }
 
 pub fn update_knight_state(
+    state_machine: Res<StateMachine<KnightState, KnightConditions>>,
     mut knight_query: Query<(
         &crate::kinematics::Velocity,
         &mut KnightState,
         &Collision<KnightSensor>,
+        &mut Crouch,
         &Animation,
     )>,
+    player_query: Query<(), With<Player>>,
 ) {
-    for (velocity, mut knight_state, collisions, animation) in knight_query.iter_mut() {
-        let new_state = if knight_state.eq(&KnightState::Attack) && !animation.finished {
-            KnightState::Attack
-        } else {
-            if !knight_state.eq(&KnightState::Attack) && collisions.get(&KnightSensor::AttackZone) {
-                KnightState::Attack
-            } else {
-                match velocity.x != 0.0 {
-                    true => KnightState::Running,
-                    false => KnightState::Idle,
-                }
-            }
+    for (velocity, mut knight_state, collisions, mut crouch, animation) in knight_query.iter_mut() {
+        if *knight_state == KnightState::Dead {
+            continue;
+        }
+
+        // a ceiling in front of the head with nothing at body height means a low passage
+        let low_ceiling_ahead =
+            collisions.get(&KnightSensor::UpFront) && !collisions.get(&KnightSensor::Front);
+        let ceiling_above = collisions.get(&KnightSensor::Up)
+            || collisions.get(&KnightSensor::UpFront)
+            || collisions.get(&KnightSensor::UpBack);
+
+        let conditions = KnightConditions {
+            crouching: collisions.get(&KnightSensor::Down)
+                && (low_ceiling_ahead || (knight_state.is_crouching() && ceiling_above)),
+            moving: velocity.x != 0.0,
+            // other hitboxes could share the group, only the player counts
+            player_in_reach: collisions
+                .find(&KnightSensor::AttackZone, |entity| {
+                    player_query.contains(entity)
+                })
+                .is_some(),
+            animation_finished: animation.is_finished(),
         };
+        let new_state = state_machine.next(*knight_state, &conditions);
 
-        println!("{:?}", new_state);
+        if crouch.crouching != new_state.is_crouching() {
+            crouch.crouching = new_state.is_crouching();
+        }
 
         knight_state.set_if_neq(new_state);
     }
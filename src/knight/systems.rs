@@ -2,17 +2,28 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
-    animation::AnimationBundle,
-    collision::{Collision, CollisionBundle, ENEMY_COLLIDER_GROUP},
+    ambient::{AmbientTint, CHARACTER_AMBIENT_STRENGTH},
+    animation::{Animation, AnimationBundle, VisualTransform},
+    collision::{Collision, CollisionBundle, HitWindow, ENEMY_COLLIDER_GROUP},
+    crouch::Crouch,
     f32_utils::move_toward,
+    health::{Health, HealthDepleted},
+    juice::Juice,
     kinematics::{Gravity, KinematicsBundle},
 };
 
 use super::{
-    animation::KnightSpritesHandles, components::Knight, sensor::KnightSensor, state::KnightState,
+    components::{Corpse, Knight, KnightDied},
+    sensor::KnightSensor,
+    state::KnightState,
 };
 
-pub fn init_knight(mut commands: Commands, knight_sprite_handles: Res<KnightSpritesHandles>) {
+const KNIGHT_HEALTH: u32 = 3;
+// how long the corpse takes to disappear once the death clip is over
+const CORPSE_FADE_DURATION: f32 = 1.0;
+
+pub fn init_knight(mut commands: Commands) {
+    let crouch = Crouch::new(13.0, 6.0, 5.5);
     commands.spawn((
         Name::new("Knight"),
         RigidBody::KinematicPositionBased,
@@ -23,11 +34,6 @@ pub fn init_knight(mut commands: Commands, knight_sprite_handles: Res<KnightSpri
         AnimationBundle::new(KnightState::Idle),
         KinematicsBundle::default(),
         SpriteSheetBundle {
-            texture_atlas: knight_sprite_handles
-                .handles
-                .get(&KnightState::Idle)
-                .unwrap()
-                .clone(),
             sprite: TextureAtlasSprite {
                 index: 0,
                 anchor: bevy::sprite::Anchor::Custom(Vec2::new(-0.03, -0.265)),
@@ -47,9 +53,18 @@ pub fn init_knight(mut commands: Commands, knight_sprite_handles: Res<KnightSpri
         },
         ENEMY_COLLIDER_GROUP,
         CollisionBundle::<KnightSensor> {
-            collider: Collider::capsule_y(13.0, 5.5),
+            collider: crouch.collider(),
             ..default()
         },
+        // bundles stop at 15 components, the visual effects are grouped
+        (
+            VisualTransform::with_pivot(Vec2::new(0.0, crouch.bottom())),
+            Juice::default(),
+            AmbientTint::new(CHARACTER_AMBIENT_STRENGTH),
+        ),
+        Health::new(KNIGHT_HEALTH),
+        HitWindow::default(),
+        crouch,
         Gravity,
     ));
 }
@@ -64,6 +79,9 @@ pub fn move_knight(
     )>,
 ) {
     for (mut velocity, mut knight, state, collision) in knight_query.iter_mut() {
+        if *state == KnightState::Dead {
+            continue;
+        }
         if collision.get(&KnightSensor::Down) && !state.eq(&KnightState::Attack) {
             let obstructed =
                 collision.get(&KnightSensor::Front) || !collision.get(&KnightSensor::DownFront);
@@ -86,11 +104,16 @@ pub fn handle_knight_collision_changes(
             Option<&mut Gravity>,
             &crate::kinematics::Velocity,
             &Collision<KnightSensor>,
+            &KnightState,
         ),
         Changed<Collision<KnightSensor>>,
     >,
 ) {
-    for (entity, gravity_marker, velocity, collisions) in player_query.iter_mut() {
+    for (entity, gravity_marker, velocity, collisions, state) in player_query.iter_mut() {
+        // the sensors of a corpse go quiet, that is no reason for it to fall
+        if *state == KnightState::Dead {
+            continue;
+        }
         match gravity_marker {
             Some(_) => {
                 if collisions.get(&KnightSensor::Down) && velocity.y <= 0.0 {
@@ -105,3 +128,75 @@ pub fn handle_knight_collision_changes(
         }
     }
 }
+
+pub fn kill_knights(
+    mut commands: Commands,
+    mut depleted: EventReader<HealthDepleted>,
+    mut deaths: EventWriter<KnightDied>,
+    mut knight_query: Query<
+        (
+            &mut KnightState,
+            &mut crate::kinematics::Velocity,
+            &GlobalTransform,
+            Option<&Children>,
+        ),
+        With<Knight>,
+    >,
+    collider_query: Query<(), With<Collider>>,
+) {
+    for HealthDepleted { entity } in depleted.iter().copied() {
+        let Ok((mut state, mut velocity, transform, children)) = knight_query.get_mut(entity)
+        else {
+            continue;
+        };
+
+        *state = KnightState::Dead;
+        velocity.x = 0.0;
+        velocity.y = 0.0;
+        // the body stays where it fell, nothing can touch it or be touched by it anymore
+        commands
+            .entity(entity)
+            .remove::<(Gravity, KinematicCharacterController)>()
+            .insert((
+                ColliderDisabled,
+                Corpse {
+                    fade: Timer::from_seconds(CORPSE_FADE_DURATION, TimerMode::Once),
+                },
+            ));
+        for &child in children.into_iter().flatten() {
+            if collider_query.contains(child) {
+                commands.entity(child).insert(ColliderDisabled);
+            }
+        }
+
+        deaths.send(KnightDied {
+            entity,
+            position: transform.translation().truncate(),
+        });
+    }
+}
+
+pub fn decay_corpses(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut corpse_query: Query<(Entity, &mut Corpse, &Animation)>,
+) {
+    for (entity, mut corpse, animation) in corpse_query.iter_mut() {
+        if !animation.is_finished() {
+            continue;
+        }
+        corpse.fade.tick(time.delta());
+        // sensors and frame boxes are children, they go with the body
+        if corpse.fade.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// runs after every other color change so the fade is what ends up on screen
+pub fn fade_corpses(mut corpse_query: Query<(&Corpse, &mut TextureAtlasSprite)>) {
+    for (corpse, mut sprite) in corpse_query.iter_mut() {
+        let alpha = corpse.fade.percent_left();
+        sprite.color.set_a(alpha);
+    }
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+
+use bevy_rapier2d::prelude::*;
+
+use crate::{collision::Hit, test_support::TestGame};
+
+use super::{
+    components::{Corpse, Knight, KnightDied},
+    state::KnightState,
+};
+
+// the knight spawns at (-30, -100), above the bottom row and between the two pillars
+#[rustfmt::skip]
+const LEVEL: &[&str] = &[
+    "         ########",
+    "#        #",
+    "##########",
+];
+const LEVEL_ORIGIN: Vec2 = Vec2::new(-80.0, -96.0);
+// floor top plus the half height of the knight capsule
+const STANDING_Y: f32 = -128.0 + 18.5;
+
+#[test]
+fn knight_patrols_between_walls() {
+    let mut game = TestGame::new().with_level(LEVEL_ORIGIN, LEVEL);
+    game.run(60);
+    assert_eq!(game.single::<KnightState, Knight>(), &KnightState::Running);
+
+    let mut turned = false;
+    let mut direction = game.single::<Knight, Knight>().direction;
+    for _ in 0..20 {
+        game.run(30);
+        let transform = game.single::<Transform, Knight>();
+        assert!((transform.translation.y - STANDING_Y).abs() < 1.0);
+        assert!(transform.translation.x > -64.0 && transform.translation.x < 64.0);
+
+        let knight = game.single::<Knight, Knight>();
+        turned |= knight.direction != direction;
+        direction = knight.direction;
+    }
+
+    assert!(turned);
+}
+
+#[test]
+fn knight_dies_once_out_of_health() {
+    let mut game = TestGame::new().with_level(LEVEL_ORIGIN, LEVEL);
+    game.run(60);
+
+    let world = game.world();
+    let knight = world.query_filtered::<Entity, With<Knight>>().single(world);
+    // the knight does not hurt itself, any entity will do as the attacker
+    let attacker = world.spawn_empty().id();
+    for _ in 0..3 {
+        game.world().send_event(Hit {
+            attacker,
+            target: knight,
+        });
+        game.run(1);
+    }
+
+    assert_eq!(game.single::<KnightState, Knight>(), &KnightState::Dead);
+    let world = game.world();
+    assert!(world.get::<Corpse>(knight).is_some());
+    assert!(world.get::<ColliderDisabled>(knight).is_some());
+    assert_eq!(world.resource::<Events<KnightDied>>().len(), 1);
+
+    // a corpse does not fall through the floor its sensors stopped touching
+    let y = game.single::<Transform, Knight>().translation.y;
+    game.run(30);
+    assert_eq!(game.single::<Transform, Knight>().translation.y, y);
+}
@@ -0,0 +1,52 @@
+#![feature(trivial_bounds)]
+#![feature(const_trait_impl)]
+#![feature(trait_alias)]
+
+pub mod afterimage;
+pub mod ambient;
+pub mod animation;
+pub mod background;
+pub mod collision;
+pub mod crouch;
+pub mod f32_utils;
+pub mod fps;
+pub mod health;
+pub mod juice;
+pub mod kinematics;
+pub mod knight;
+pub mod map;
+pub mod particles;
+pub mod physics;
+pub mod player;
+pub mod pushable;
+pub mod sprite_effects;
+pub mod state_machine;
+
+#[cfg(test)]
+mod test_support;
+
+use bevy::prelude::*;
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum GameSet {
+    BeforeUpdate,
+    Update,
+    AfterUpdate,
+    Render,
+}
+
+pub struct GameSetsPlugin;
+
+impl Plugin for GameSetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            (
+                GameSet::BeforeUpdate,
+                GameSet::Update,
+                GameSet::AfterUpdate,
+                GameSet::Render,
+            )
+                .chain(),
+        );
+    }
+}
@@ -1,45 +1,28 @@
-#![feature(trivial_bounds)]
-#![feature(const_trait_impl)]
-#![feature(trait_alias)]
-
-mod animation;
-mod background;
-mod collision;
-mod f32_utils;
-mod fps;
-mod kinematics;
-mod knight;
-mod map;
-mod physics;
-mod player;
-mod texture_utils;
-
-use animation::animate;
-use background::BackgroundPlugin;
 use bevy::{
     input::mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
 };
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier2d::prelude::*;
-use fps::FpsPlugin;
-use kinematics::KinematicsPlugin;
-use knight::KnightPlugin;
-use map::MapPlugin;
-use physics::PhysicsExtensionPlugin;
-use player::PlayerPlugin;
-
-#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
-enum GameSet {
-    BeforeUpdate,
-    Update,
-    AfterUpdate,
-    Render,
-}
+use platformer_2d::{
+    afterimage::AfterimagePlugin, ambient::AmbientPlugin, animation::animate,
+    background::BackgroundPlugin, fps::FpsPlugin, juice::JuicePlugin, kinematics::KinematicsPlugin,
+    knight::KnightPlugin, map::MapPlugin, particles::ParticlesPlugin,
+    physics::PhysicsExtensionPlugin, player::PlayerPlugin, pushable::PushablePlugin,
+    sprite_effects::SpriteEffectsPlugin, GameSet, GameSetsPlugin,
+};
 
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                .set(AssetPlugin {
+                    // animation sheets are hot reloaded while the game runs
+                    watch_for_changes: true,
+                    ..default()
+                }),
+        )
         .add_plugin(WorldInspectorPlugin::new())
         .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
         .add_plugin(PhysicsExtensionPlugin)
@@ -50,15 +33,13 @@ fn main() {
         .add_plugin(PlayerPlugin)
         .add_plugin(KinematicsPlugin)
         .add_plugin(KnightPlugin)
-        .configure_sets(
-            (
-                GameSet::BeforeUpdate,
-                GameSet::Update,
-                GameSet::AfterUpdate,
-                GameSet::Render,
-            )
-                .chain(),
-        )
+        .add_plugin(PushablePlugin)
+        .add_plugin(SpriteEffectsPlugin)
+        .add_plugin(ParticlesPlugin)
+        .add_plugin(AfterimagePlugin)
+        .add_plugin(JuicePlugin)
+        .add_plugin(AmbientPlugin)
+        .add_plugin(GameSetsPlugin)
         .add_startup_system(setup_camera)
         .add_system(animate.in_set(GameSet::Render))
         .add_system(zoom)
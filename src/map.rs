@@ -1,8 +1,10 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::collision::GROUND_GROUP;
+use crate::{collision::GROUND_GROUP, pushable::CrateBundle};
+
+const TILE_SIZE: f32 = 16.0;
 
 pub struct MapPlugin;
 
@@ -15,10 +17,24 @@ impl Plugin for MapPlugin {
                 level_background: LevelBackground::Nonexistent,
                 ..default()
             })
-            .add_system(add_collider)
+            .add_plugin(WallPlugin)
             .register_ldtk_int_cell::<WallBundle>(1)
             .register_ldtk_int_cell::<WallBundle>(2)
-            .register_ldtk_int_cell::<WallBundle>(3);
+            .register_ldtk_int_cell::<WallBundle>(3)
+            .register_ldtk_entity::<CrateBundle>("Crate");
+    }
+}
+
+/// Turns `Wall` entities into solid ground, independently of where they come from.
+pub struct WallPlugin;
+
+impl Plugin for WallPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TileGrid>()
+            .add_system(add_collider)
+            // a reloaded level is only aligned on its own tiles once the old ones are gone
+            .add_system(register_wall_tiles.after(unregister_wall_tiles))
+            .add_system(unregister_wall_tiles);
     }
 }
 
@@ -49,3 +65,100 @@ pub struct Wall;
 pub struct WallBundle {
     wall: Wall,
 }
+
+/// Solid cells of the level, indexed by grid position rather than by collider so queries stay
+/// valid however the wall colliders end up being shaped.
+#[derive(Resource)]
+pub struct TileGrid {
+    tile_size: f32,
+    origin: Vec2,
+    cells: HashMap<IVec2, Entity>,
+}
+
+impl Default for TileGrid {
+    fn default() -> Self {
+        Self::new(TILE_SIZE)
+    }
+}
+
+impl TileGrid {
+    pub fn new(tile_size: f32) -> Self {
+        Self {
+            tile_size,
+            origin: Vec2::ZERO,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, center: Vec2) {
+        // every tile of a level shares the same alignment, the first one is as good as any. A grid
+        // emptied by unloading a level takes the alignment of the next one
+        if self.cells.is_empty() {
+            self.origin = center - Vec2::splat(self.tile_size / 2.0);
+        }
+        self.cells.insert(self.cell(center), entity);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        self.cells.retain(|_, tile| *tile != entity);
+        if self.cells.is_empty() {
+            self.origin = Vec2::ZERO;
+        }
+    }
+
+    pub fn cell(&self, position: Vec2) -> IVec2 {
+        ((position - self.origin) / self.tile_size)
+            .floor()
+            .as_ivec2()
+    }
+
+    pub fn is_solid(&self, cell: IVec2) -> bool {
+        self.cells.contains_key(&cell)
+    }
+
+    /// Looks for the top edge of a solid tile in the column `hand` reaches into, within `reach`
+    /// of the hand height and with two free cells above it to climb onto. Returns the corner of
+    /// the ledge on the side the character faces.
+    pub fn find_ledge(&self, hand: Vec2, direction: f32, reach: f32) -> Option<Vec2> {
+        let column = self.cell(hand).x;
+        let lowest = self.cell(hand - Vec2::Y * reach).y;
+        let highest = self.cell(hand + Vec2::Y * reach).y;
+
+        (lowest..=highest)
+            .filter(|&row| {
+                self.is_solid(IVec2::new(column, row))
+                    && !self.is_solid(IVec2::new(column, row + 1))
+                    && !self.is_solid(IVec2::new(column, row + 2))
+            })
+            .map(|row| {
+                let face = match direction.is_sign_positive() {
+                    true => column,
+                    false => column + 1,
+                };
+                self.origin + Vec2::new(face as f32, (row + 1) as f32) * self.tile_size
+            })
+            .filter(|corner| (corner.y - hand.y).abs() <= reach)
+            .min_by(|a, b| (a.y - hand.y).abs().total_cmp(&(b.y - hand.y).abs()))
+    }
+}
+
+fn register_wall_tiles(
+    mut tile_grid: ResMut<TileGrid>,
+    wall_query: Query<(Entity, &GlobalTransform), (With<Wall>, Changed<GlobalTransform>)>,
+) {
+    for (entity, transform) in wall_query.iter() {
+        tile_grid.insert(entity, transform.translation().truncate());
+    }
+}
+
+fn unregister_wall_tiles(
+    mut tile_grid: ResMut<TileGrid>,
+    mut removed_walls: RemovedComponents<Wall>,
+) {
+    for entity in removed_walls.iter() {
+        tile_grid.remove(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests;
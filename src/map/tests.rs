@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use super::TileGrid;
+
+fn entity(index: u32) -> Entity {
+    Entity::from_raw(index)
+}
+
+#[test]
+fn an_emptied_grid_follows_the_next_level() {
+    let mut grid = TileGrid::new(16.0);
+    grid.insert(entity(0), Vec2::new(8.0, 8.0));
+    grid.insert(entity(1), Vec2::new(24.0, 8.0));
+    grid.remove(entity(0));
+    grid.remove(entity(1));
+
+    // the next level is offset by half a tile
+    grid.insert(entity(2), Vec2::new(0.0, 0.0));
+    assert_eq!(grid.cell(Vec2::new(0.0, 0.0)), IVec2::ZERO);
+    assert_eq!(grid.cell(Vec2::new(-7.0, 7.0)), IVec2::ZERO);
+    assert!(grid.is_solid(IVec2::ZERO));
+    assert!(!grid.is_solid(IVec2::new(-1, 0)));
+}
+
+// a wall of `rows` tiles in `column`, inserted in the given order
+fn wall(grid: &mut TileGrid, column: i32, rows: &[i32]) {
+    for &row in rows {
+        let center = Vec2::new(column as f32, row as f32) * 16.0 + Vec2::splat(8.0);
+        grid.insert(entity(grid.cells.len() as u32), center);
+    }
+}
+
+#[test]
+fn ledges_are_found_within_reach_facing_either_way() {
+    let mut grid = TileGrid::new(16.0);
+    // top edge at y 48, left face at x 32 and right face at x 48
+    wall(&mut grid, 2, &[0, 1, 2]);
+
+    assert_eq!(
+        grid.find_ledge(Vec2::new(34.0, 45.0), 1.0, 6.0),
+        Some(Vec2::new(32.0, 48.0))
+    );
+    assert_eq!(
+        grid.find_ledge(Vec2::new(46.0, 51.0), -1.0, 6.0),
+        Some(Vec2::new(48.0, 48.0))
+    );
+}
+
+#[test]
+fn ledges_need_room_to_climb_onto() {
+    let mut grid = TileGrid::new(16.0);
+    // the second cell above the top tile is taken
+    wall(&mut grid, 2, &[0, 1, 2, 4]);
+
+    assert_eq!(grid.find_ledge(Vec2::new(34.0, 45.0), 1.0, 6.0), None);
+}
+
+#[test]
+fn ledges_out_of_reach_are_ignored() {
+    let mut grid = TileGrid::new(16.0);
+    wall(&mut grid, 2, &[0, 1, 2]);
+
+    assert_eq!(grid.find_ledge(Vec2::new(34.0, 30.0), 1.0, 6.0), None);
+    assert_eq!(grid.find_ledge(Vec2::new(34.0, 60.0), 1.0, 6.0), None);
+}
+
+#[test]
+fn cells_do_not_depend_on_the_insertion_order() {
+    let mut grid = TileGrid::new(16.0);
+    wall(&mut grid, 2, &[2, 0, 1]);
+    wall(&mut grid, -1, &[1]);
+
+    for (column, row) in [(2, 0), (2, 1), (2, 2), (-1, 1)] {
+        let center = Vec2::new(column as f32, row as f32) * 16.0 + Vec2::splat(8.0);
+        let cell = grid.cell(center);
+        assert!(grid.is_solid(cell));
+        // cells are counted from the first tile inserted, they only have to be consistent
+        assert_eq!(
+            cell - grid.cell(Vec2::new(40.0, 40.0)),
+            IVec2::new(column - 2, row - 2)
+        );
+    }
+    assert!(!grid.is_solid(grid.cell(Vec2::new(40.0, 56.0))));
+    assert_eq!(
+        grid.find_ledge(Vec2::new(34.0, 45.0), 1.0, 6.0),
+        Some(Vec2::new(32.0, 48.0))
+    );
+}
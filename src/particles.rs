@@ -0,0 +1,474 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, render::texture::DEFAULT_IMAGE_HANDLE};
+
+use crate::{
+    collision::Hit,
+    crouch::Crouch,
+    kinematics::{cancel_gravity, Gravity, Orientation, Velocity},
+    GameSet,
+};
+
+#[cfg(test)]
+mod tests;
+
+// above the characters and their layers
+const PARTICLE_Z: f32 = 5.0;
+const DEFAULT_POOL_CAPACITY: usize = 512;
+// kinematic velocities are in pixels per frame, landing softer than this raises no dust
+const LANDING_DUST_MIN_SPEED: f32 = 1.5;
+const LANDING_DUST_COUNT: u32 = 8;
+const HIT_SPARK_COUNT: u32 = 10;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParticlePool>()
+            .add_event::<ParticleBurst>()
+            .add_system(spark_hits.in_set(GameSet::Update))
+            .add_system(
+                raise_landing_dust
+                    .in_set(GameSet::AfterUpdate)
+                    .before(cancel_gravity),
+            )
+            .add_system(run_emitters.in_set(GameSet::AfterUpdate))
+            .add_system(update_particles.in_set(GameSet::Render))
+            .add_system(
+                spawn_particles
+                    .in_set(GameSet::Render)
+                    .after(update_particles),
+            );
+    }
+}
+
+/// Atlas frames played over the life of a particle, `count` frames starting at `first`.
+#[derive(Debug, Clone)]
+pub struct ParticleFrames {
+    pub atlas: Handle<TextureAtlas>,
+    pub first: usize,
+    pub count: usize,
+}
+
+/// What a particle looks like and how it moves, velocities are in pixels per second.
+#[derive(Debug, Clone)]
+pub struct ParticleEffect {
+    pub lifetime: f32,
+    /// Particles leave along `direction`, turned by up to `spread` radians either way.
+    pub direction: Vec2,
+    pub spread: f32,
+    pub speed: f32,
+    /// Each particle gets a speed up to `speed_variation` away from `speed`.
+    pub speed_variation: f32,
+    pub gravity: f32,
+    pub start_color: Color,
+    pub end_color: Color,
+    pub start_size: f32,
+    pub end_size: f32,
+    /// Plain squares when `None`.
+    pub frames: Option<ParticleFrames>,
+}
+
+impl Default for ParticleEffect {
+    fn default() -> Self {
+        Self {
+            lifetime: 0.5,
+            direction: Vec2::Y,
+            spread: PI,
+            speed: 20.0,
+            speed_variation: 0.0,
+            gravity: 0.0,
+            start_color: Color::WHITE,
+            end_color: Color::WHITE,
+            start_size: 1.0,
+            end_size: 1.0,
+            frames: None,
+        }
+    }
+}
+
+impl ParticleEffect {
+    pub fn dust() -> Self {
+        Self {
+            lifetime: 0.4,
+            direction: Vec2::Y,
+            spread: PI / 2.0,
+            speed: 12.0,
+            speed_variation: 6.0,
+            gravity: 10.0,
+            start_color: Color::rgba(0.85, 0.8, 0.7, 0.8),
+            end_color: Color::rgba(0.85, 0.8, 0.7, 0.0),
+            start_size: 3.0,
+            end_size: 1.0,
+            ..default()
+        }
+    }
+
+    pub fn sparks() -> Self {
+        Self {
+            lifetime: 0.25,
+            direction: Vec2::X,
+            spread: PI / 3.0,
+            speed: 90.0,
+            speed_variation: 40.0,
+            gravity: 200.0,
+            start_color: Color::rgb(1.0, 0.95, 0.6),
+            end_color: Color::rgba(1.0, 0.4, 0.1, 0.0),
+            start_size: 2.0,
+            end_size: 1.0,
+            ..default()
+        }
+    }
+}
+
+/// Asks for `count` particles of `effect` at `position`, `mirrored` flips their direction.
+pub struct ParticleBurst {
+    pub effect: ParticleEffect,
+    pub position: Vec2,
+    pub count: u32,
+    pub mirrored: bool,
+}
+
+/// Keeps emitting particles at `rate` per second while active, mirrored by the [`Orientation`] of
+/// the entity when it has one.
+#[derive(Component, Debug, Clone)]
+pub struct ParticleEmitter {
+    pub effect: ParticleEffect,
+    pub rate: f32,
+    pub offset: Vec2,
+    pub active: bool,
+    pending: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: ParticleEffect, rate: f32) -> Self {
+        Self {
+            effect,
+            rate,
+            offset: Vec2::ZERO,
+            active: true,
+            pending: 0.0,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Number of particles due after `delta` seconds, the fraction left carries over.
+    pub fn tick(&mut self, delta: f32) -> u32 {
+        if !self.active {
+            self.pending = 0.0;
+            return 0;
+        }
+        self.pending += self.rate * delta;
+        let count = self.pending.floor();
+        self.pending -= count;
+        count as u32
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Particle {
+    velocity: Vec2,
+    gravity: f32,
+    age: f32,
+    lifetime: f32,
+    start_color: Color,
+    end_color: Color,
+    start_size: f32,
+    end_size: f32,
+    frames: Option<(usize, usize)>,
+}
+
+impl Particle {
+    fn new(effect: &ParticleEffect, velocity: Vec2) -> Self {
+        Self {
+            velocity,
+            gravity: effect.gravity,
+            age: 0.0,
+            lifetime: effect.lifetime,
+            start_color: effect.start_color,
+            end_color: effect.end_color,
+            start_size: effect.start_size,
+            end_size: effect.end_size,
+            frames: effect
+                .frames
+                .as_ref()
+                .map(|frames| (frames.first, frames.count)),
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+
+    fn progress(&self) -> f32 {
+        (self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+
+    /// Ages the particle by `delta` seconds and returns how far it moved.
+    pub fn tick(&mut self, delta: f32) -> Vec2 {
+        self.age += delta;
+        self.velocity.y -= self.gravity * delta;
+        self.velocity * delta
+    }
+
+    pub fn color(&self) -> Color {
+        let t = self.progress();
+        let [r, g, b, a] = self.start_color.as_rgba_f32();
+        let [end_r, end_g, end_b, end_a] = self.end_color.as_rgba_f32();
+        Color::rgba(
+            r + (end_r - r) * t,
+            g + (end_g - g) * t,
+            b + (end_b - b) * t,
+            a + (end_a - a) * t,
+        )
+    }
+
+    pub fn size(&self) -> f32 {
+        self.start_size + (self.end_size - self.start_size) * self.progress()
+    }
+
+    pub fn frame(&self) -> usize {
+        match self.frames {
+            Some((first, count)) => {
+                first + ((self.progress() * count as f32) as usize).min(count.saturating_sub(1))
+            }
+            None => 0,
+        }
+    }
+}
+
+/// Particle entities are never despawned, dead ones are hidden and handed out again.
+#[derive(Resource)]
+pub struct ParticlePool {
+    pub capacity: usize,
+    free: Vec<Entity>,
+    spawned: usize,
+    // plain particles are a single white frame, so every particle is an atlas sprite
+    white: Handle<TextureAtlas>,
+    seed: u32,
+}
+
+impl FromWorld for ParticlePool {
+    fn from_world(world: &mut World) -> Self {
+        let mut atlases = world.resource_mut::<Assets<TextureAtlas>>();
+        let mut white = TextureAtlas::new_empty(DEFAULT_IMAGE_HANDLE.typed(), Vec2::ONE);
+        white.add_texture(Rect::new(0.0, 0.0, 1.0, 1.0));
+        Self {
+            capacity: DEFAULT_POOL_CAPACITY,
+            free: Vec::new(),
+            spawned: 0,
+            white: atlases.add(white),
+            seed: 0x9e37_79b9,
+        }
+    }
+}
+
+impl ParticlePool {
+    /// Particle entities created so far, alive or waiting to be reused.
+    pub fn spawned(&self) -> usize {
+        self.spawned
+    }
+
+    // no need for a rand dependency, a xorshift is plenty for particles
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32
+    }
+
+    // between -1 and 1
+    fn random_signed(&mut self) -> f32 {
+        self.random() * 2.0 - 1.0
+    }
+}
+
+fn spawn_particles(
+    mut commands: Commands,
+    mut pool: ResMut<ParticlePool>,
+    mut bursts: EventReader<ParticleBurst>,
+    mut particle_query: Query<(
+        &mut Particle,
+        &mut Transform,
+        &mut TextureAtlasSprite,
+        &mut Handle<TextureAtlas>,
+        &mut Visibility,
+    )>,
+) {
+    for burst in bursts.iter() {
+        let effect = &burst.effect;
+        let atlas = match &effect.frames {
+            Some(frames) => frames.atlas.clone(),
+            None => pool.white.clone(),
+        };
+        let mut direction = effect.direction.normalize_or_zero();
+        if burst.mirrored {
+            direction.x = -direction.x;
+        }
+
+        for _ in 0..burst.count {
+            let angle = pool.random_signed() * effect.spread;
+            let speed = effect.speed + pool.random_signed() * effect.speed_variation;
+            let particle = Particle::new(effect, Vec2::from_angle(angle).rotate(direction) * speed);
+            let sprite = TextureAtlasSprite {
+                index: particle.frame(),
+                color: particle.color(),
+                custom_size: effect
+                    .frames
+                    .is_none()
+                    .then(|| Vec2::splat(particle.size())),
+                flip_x: burst.mirrored,
+                ..default()
+            };
+            let transform = Transform::from_translation(burst.position.extend(PARTICLE_Z));
+
+            if let Some(entity) = pool.free.pop() {
+                if let Ok((
+                    mut pooled,
+                    mut pooled_transform,
+                    mut pooled_sprite,
+                    mut pooled_atlas,
+                    mut visibility,
+                )) = particle_query.get_mut(entity)
+                {
+                    *pooled = particle;
+                    *pooled_transform = transform;
+                    *pooled_sprite = sprite;
+                    *pooled_atlas = atlas.clone();
+                    *visibility = Visibility::Inherited;
+                }
+            } else if pool.spawned < pool.capacity {
+                pool.spawned += 1;
+                commands.spawn((
+                    Name::new("Particle"),
+                    particle,
+                    SpriteSheetBundle {
+                        sprite,
+                        texture_atlas: atlas.clone(),
+                        transform,
+                        ..default()
+                    },
+                ));
+            }
+            // a full pool drops the particles, effects are never worth stalling for
+        }
+    }
+}
+
+fn update_particles(
+    time: Res<Time>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut TextureAtlasSprite,
+        &mut Visibility,
+    )>,
+) {
+    for (entity, mut particle, mut transform, mut sprite, mut visibility) in
+        particle_query.iter_mut()
+    {
+        if !particle.is_alive() {
+            continue;
+        }
+
+        let movement = particle.tick(time.delta_seconds());
+        transform.translation += movement.extend(0.0);
+
+        if !particle.is_alive() {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+
+        sprite.color = particle.color();
+        match particle.frames {
+            Some(_) => sprite.index = particle.frame(),
+            None => sprite.custom_size = Some(Vec2::splat(particle.size())),
+        }
+    }
+}
+
+fn run_emitters(
+    time: Res<Time>,
+    mut bursts: EventWriter<ParticleBurst>,
+    mut emitter_query: Query<(&mut ParticleEmitter, &GlobalTransform, Option<&Orientation>)>,
+) {
+    for (mut emitter, transform, orientation) in emitter_query.iter_mut() {
+        let count = emitter.tick(time.delta_seconds());
+        if count == 0 {
+            continue;
+        }
+
+        let mirrored = orientation == Some(&Orientation::Left);
+        let mut offset = emitter.offset;
+        if mirrored {
+            offset.x = -offset.x;
+        }
+        bursts.send(ParticleBurst {
+            effect: emitter.effect.clone(),
+            position: transform.translation().truncate() + offset,
+            count,
+            mirrored,
+        });
+    }
+}
+
+// gravity is removed on touchdown, the fall speed is still there until cancel_gravity runs
+fn raise_landing_dust(
+    mut removed_gravity: RemovedComponents<Gravity>,
+    mut bursts: EventWriter<ParticleBurst>,
+    character_query: Query<(&GlobalTransform, &Velocity, Option<&Crouch>), Without<Gravity>>,
+) {
+    for entity in removed_gravity.iter() {
+        let Ok((transform, velocity, crouch)) = character_query.get(entity) else {
+            continue;
+        };
+        if -velocity.y < LANDING_DUST_MIN_SPEED {
+            continue;
+        }
+
+        let feet = transform.translation().truncate()
+            + Vec2::new(0.0, crouch.map_or(0.0, |crouch| crouch.bottom()));
+        // a puff on each side of the feet
+        for mirrored in [false, true] {
+            bursts.send(ParticleBurst {
+                effect: ParticleEffect {
+                    direction: Vec2::new(1.0, 0.3),
+                    spread: PI / 8.0,
+                    speed: 10.0 + 4.0 * -velocity.y,
+                    ..ParticleEffect::dust()
+                },
+                position: feet,
+                count: LANDING_DUST_COUNT / 2,
+                mirrored,
+            });
+        }
+    }
+}
+
+fn spark_hits(
+    mut hits: EventReader<Hit>,
+    mut bursts: EventWriter<ParticleBurst>,
+    transform_query: Query<&GlobalTransform>,
+) {
+    for hit in hits.iter() {
+        let Ok([attacker, target]) = transform_query.get_many([hit.attacker, hit.target]) else {
+            continue;
+        };
+        let attacker = attacker.translation().truncate();
+        let target = target.translation().truncate();
+        bursts.send(ParticleBurst {
+            effect: ParticleEffect::sparks(),
+            // on the side of the target the blow comes from
+            position: target.lerp(attacker, 0.25),
+            count: HIT_SPARK_COUNT,
+            mirrored: attacker.x > target.x,
+        });
+    }
+}
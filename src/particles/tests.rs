@@ -0,0 +1,102 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{collision::Hit, kinematics::KinematicsPlugin, test_support::FRAME, GameSetsPlugin};
+
+use super::{
+    Particle, ParticleBurst, ParticleEffect, ParticleEmitter, ParticleFrames, ParticlePool,
+    ParticlesPlugin,
+};
+
+#[test]
+fn particles_fade_and_fall_over_their_lifetime() {
+    let effect = ParticleEffect {
+        lifetime: 1.0,
+        gravity: 2.0,
+        start_color: Color::rgba(1.0, 1.0, 1.0, 1.0),
+        end_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+        start_size: 4.0,
+        end_size: 2.0,
+        ..default()
+    };
+    let mut particle = Particle::new(&effect, Vec2::new(1.0, 0.0));
+
+    let movement = particle.tick(0.5);
+    assert_eq!(movement, Vec2::new(0.5, -0.5));
+    assert_eq!(particle.color(), Color::rgba(0.5, 0.5, 0.5, 0.5));
+    assert_eq!(particle.size(), 3.0);
+    assert!(particle.is_alive());
+
+    particle.tick(0.5);
+    assert!(!particle.is_alive());
+}
+
+#[test]
+fn particle_frames_play_over_the_lifetime() {
+    let effect = ParticleEffect {
+        lifetime: 1.0,
+        frames: Some(ParticleFrames {
+            atlas: Handle::default(),
+            first: 4,
+            count: 4,
+        }),
+        ..default()
+    };
+    let mut particle = Particle::new(&effect, Vec2::ZERO);
+    assert_eq!(particle.frame(), 4);
+    particle.tick(0.5);
+    assert_eq!(particle.frame(), 6);
+    particle.tick(0.5);
+    assert_eq!(particle.frame(), 7);
+}
+
+#[test]
+fn emitters_carry_the_fraction_of_a_particle_over() {
+    let mut emitter = ParticleEmitter::new(ParticleEffect::default(), 12.0);
+    assert_eq!(emitter.tick(0.125), 1);
+    assert_eq!(emitter.tick(0.125), 2);
+    assert_eq!(emitter.tick(0.25), 3);
+
+    emitter.active = false;
+    assert_eq!(emitter.tick(1.0), 0);
+}
+
+#[test]
+fn dead_particles_are_reused() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_asset::<TextureAtlas>()
+        .add_event::<Hit>()
+        .add_plugin(KinematicsPlugin)
+        .add_plugin(GameSetsPlugin)
+        .add_plugin(ParticlesPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+    let burst = || ParticleBurst {
+        effect: ParticleEffect {
+            lifetime: 0.1,
+            ..default()
+        },
+        position: Vec2::ZERO,
+        count: 4,
+        mirrored: false,
+    };
+
+    app.world.send_event(burst());
+    app.update();
+    assert_eq!(app.world.resource::<ParticlePool>().spawned(), 4);
+
+    // well past the lifetime, then a new burst takes the place of the dead particles
+    for _ in 0..10 {
+        app.update();
+    }
+    app.world.send_event(burst());
+    app.update();
+
+    assert_eq!(app.world.resource::<ParticlePool>().spawned(), 4);
+    let mut particle_query = app.world.query::<(&Particle, &Visibility)>();
+    let visible = particle_query
+        .iter(&app.world)
+        .filter(|(particle, visibility)| particle.is_alive() && **visibility != Visibility::Hidden)
+        .count();
+    assert_eq!(visible, 4);
+}
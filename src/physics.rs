@@ -9,6 +9,8 @@ impl Plugin for PhysicsExtensionPlugin {
     }
 }
 
+// sensor children have no GlobalTransform, so rapier never sees them move and their offset is
+// copied here. Their shape needs nothing, rapier applies every Changed<Collider> by itself
 fn apply_child_collider_transform_changes(
     mut context: ResMut<RapierContext>,
     collider_query: Query<
@@ -5,4 +5,8 @@ mod sensor;
 mod state;
 mod systems;
 
+#[cfg(test)]
+mod tests;
+
+pub use components::Player;
 pub use plugin::PlayerPlugin;
@@ -3,6 +3,13 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct Player {
     pub move_speed: f32,
+    pub crouch_speed: f32,
     pub acceleration: f32,
     pub jump_impulse: f32,
 }
+
+#[derive(Component, Default)]
+pub struct LedgeGrab {
+    pub ledge: Option<Vec2>,
+    pub cooldown: Timer,
+}
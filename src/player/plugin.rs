@@ -1,14 +1,15 @@
 use bevy::prelude::*;
 
-use crate::{animation::AnimationPlugin, collision::CollisionPlugin, GameSet};
+use crate::{
+    animation::AnimationPlugin, collision::CollisionPlugin, crouch::CrouchPlugin, GameSet,
+};
 
 use super::{
-    animation::{load_player_textures, PlayerSpritesHandles},
     sensor::PlayerSensor,
-    state::PlayerState,
+    state::{player_state_machine, PlayerState},
     systems::{
-        follow_player, handle_player_collision_changes, init_player, move_player,
-        update_player_state,
+        emit_running_dust, follow_player, grab_ledge, handle_player_collision_changes, init_player,
+        move_player, scale_walk_animation, trail_fast_falls, update_player_state,
     },
 };
 
@@ -16,11 +17,13 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<PlayerSpritesHandles>()
-            .add_startup_system(load_player_textures)
-            .add_startup_system(init_player.after(load_player_textures))
+        app.insert_resource(player_state_machine())
+            .add_startup_system(init_player)
             .add_plugin(CollisionPlugin::<PlayerSensor>::default())
-            .add_plugin(AnimationPlugin::<PlayerState, PlayerSpritesHandles>::default())
+            .add_plugin(CrouchPlugin::<PlayerSensor>::default())
+            .add_plugin(AnimationPlugin::<PlayerState>::new(
+                "adventurer/Adventurer.aseprite.json",
+            ))
             .add_system(
                 move_player
                     .in_set(GameSet::Update)
@@ -31,7 +34,16 @@ impl Plugin for PlayerPlugin {
                     .in_set(GameSet::Update)
                     .before(update_player_state),
             )
+            .add_system(
+                grab_ledge
+                    .in_set(GameSet::Update)
+                    .after(move_player)
+                    .before(update_player_state),
+            )
             .add_system(update_player_state.in_set(GameSet::Update))
-            .add_system(follow_player.in_set(GameSet::AfterUpdate));
+            .add_system(follow_player.in_set(GameSet::AfterUpdate))
+            .add_system(scale_walk_animation.in_set(GameSet::AfterUpdate))
+            .add_system(emit_running_dust.in_set(GameSet::AfterUpdate))
+            .add_system(trail_fast_falls.in_set(GameSet::AfterUpdate));
     }
 }
@@ -8,6 +8,8 @@ pub enum PlayerSensor {
     Up,
     Down,
     Hitbox,
+    LedgeFront,
+    LedgeAbove,
 }
 
 impl CollisionSensor for PlayerSensor {
@@ -31,6 +33,25 @@ impl CollisionSensor for PlayerSensor {
                 Vec2::new(0.0, 0.0),
                 PLAYER_HITBOX_GROUP,
             ),
+            (
+                Self::LedgeFront,
+                Collider::cuboid(1.0, 2.0),
+                Vec2::new(6.0, 10.0),
+                GROUND_SENSOR_GROUP,
+            ),
+            (
+                Self::LedgeAbove,
+                Collider::cuboid(1.0, 2.0),
+                Vec2::new(6.0, 17.0),
+                GROUND_SENSOR_GROUP,
+            ),
         ]
     }
+
+    fn get_crouching_sensor(&self) -> Option<(Collider, Vec2)> {
+        match self {
+            Self::Hitbox => Some((Collider::capsule_y(5.0, 4.0), Vec2::new(0.0, -5.0))),
+            _ => None,
+        }
+    }
 }
@@ -1,19 +1,64 @@
 use bevy::prelude::*;
+use strum::{EnumIter, IntoStaticStr};
 
-#[derive(Component, Debug, PartialEq, Eq)]
+use crate::state_machine::StateMachine;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr, EnumIter)]
 pub enum PlayerState {
     Idle,
     Run,
     Rising,
     Falling,
     Attack1,
+    Crouch,
+    CrouchWalk,
+    Hanging,
+    Climbing,
 }
 
 impl PlayerState {
     pub fn can_move(&self) -> bool {
         match self {
-            PlayerState::Attack1 => false,
+            PlayerState::Attack1 | PlayerState::Hanging | PlayerState::Climbing => false,
             _ => true,
         }
     }
+
+    pub fn is_crouching(&self) -> bool {
+        match self {
+            PlayerState::Crouch | PlayerState::CrouchWalk => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_on_ledge(&self) -> bool {
+        match self {
+            PlayerState::Hanging | PlayerState::Climbing => true,
+            _ => false,
+        }
+    }
+}
+
+/// What the player state machine decides on, gathered every frame.
+pub struct PlayerConditions {
+    pub rising: bool,
+    pub grounded: bool,
+    pub moving: bool,
+    pub crouch_requested: bool,
+    pub attack_pressed: bool,
+    pub animation_finished: bool,
+}
+
+// ledge states are left out, they are driven by grab_ledge
+pub fn player_state_machine() -> StateMachine<PlayerState, PlayerConditions> {
+    StateMachine::default()
+        .interrupt(PlayerState::Rising, |c| c.rising)
+        .interrupt(PlayerState::Falling, |c| !c.grounded)
+        .lock(PlayerState::Attack1, |c| !c.animation_finished)
+        .from_any(PlayerState::CrouchWalk, |c| c.crouch_requested && c.moving)
+        .from_any(PlayerState::Crouch, |c| c.crouch_requested)
+        // TODO : need to orient the character in the direction the player is aiming to
+        .from_any(PlayerState::Attack1, |c| c.attack_pressed)
+        .from_any(PlayerState::Run, |c| c.moving)
+        .from_any(PlayerState::Idle, |_| true)
 }
@@ -2,35 +2,54 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
-    animation::{Animation, AnimationBundle, AnimationHandles},
+    afterimage::Afterimage,
+    ambient::{AmbientTint, CHARACTER_AMBIENT_STRENGTH},
+    animation::{Animation, AnimationBundle, VisualTransform},
     collision::{Collision, CollisionBundle, PLAYER_COLLIDER_GROUP},
+    crouch::Crouch,
     f32_utils::move_toward,
-    kinematics::{Gravity, KinematicsBundle},
+    juice::Juice,
+    kinematics::{Gravity, KinematicsBundle, Orientation},
+    map::TileGrid,
+    particles::{ParticleEffect, ParticleEmitter},
+    state_machine::StateMachine,
 };
 
 use super::{
-    animation::PlayerSpritesHandles, components::Player, sensor::PlayerSensor, state::PlayerState,
+    components::{LedgeGrab, Player},
+    sensor::PlayerSensor,
+    state::{PlayerConditions, PlayerState},
 };
 
-pub fn init_player(mut commands: Commands, player_sprite_handles: Res<PlayerSpritesHandles>) {
+// where the hands are relative to the player origin when facing right
+const HAND_OFFSET: Vec2 = Vec2::new(5.0, 10.0);
+// how far from the hands the top of a ledge can be and still be grabbed
+const LEDGE_REACH: f32 = 6.0;
+// where the player origin ends up relative to the ledge corner after climbing up
+const CLIMB_OFFSET: Vec2 = Vec2::new(6.0, 14.5);
+const LEDGE_COOLDOWN: f32 = 0.3;
+// slowest playback of the walk cycles, so the feet keep moving when starting to walk
+const MIN_WALK_ANIMATION_SPEED: f32 = 0.3;
+// dust puffs per second kicked up while running
+const RUNNING_DUST_RATE: f32 = 12.0;
+// falling faster than this leaves a trail of ghosts
+const FAST_FALL_SPEED: f32 = 4.0;
+
+pub fn init_player(mut commands: Commands) {
+    let crouch = Crouch::new(10.0, 5.0, 4.0);
     commands.spawn((
         Name::new("Player"),
         RigidBody::KinematicPositionBased,
         Gravity,
         Player {
             move_speed: 1.5,
+            crouch_speed: 0.75,
             acceleration: 8.0,
             jump_impulse: 4.5,
         },
         AnimationBundle::new(PlayerState::Idle),
         KinematicsBundle::default(),
         SpriteSheetBundle {
-            texture_atlas: player_sprite_handles.get_handle(&PlayerState::Idle),
-            sprite: TextureAtlasSprite {
-                index: 0,
-                anchor: bevy::sprite::Anchor::Custom(Vec2::new(0., -0.09)),
-                ..default()
-            },
             transform: Transform {
                 translation: Vec3::new(82.0, -70.0, 0.0),
                 ..default()
@@ -41,13 +60,41 @@ pub fn init_player(mut commands: Commands, player_sprite_handles: Res<PlayerSpri
             snap_to_ground: None,
             autostep: None,
             filter_groups: Some(PLAYER_COLLIDER_GROUP),
+            // walking into a crate pushes it, the mass decides how hard
+            apply_impulse_to_dynamic_bodies: true,
+            custom_mass: Some(2.0),
             ..default()
         },
         PLAYER_COLLIDER_GROUP,
         CollisionBundle::<PlayerSensor> {
-            collider: Collider::capsule_y(10.0, 4.0),
+            collider: crouch.collider(),
             ..default()
         },
+        // bundles stop at 15 components, the visual effects are grouped
+        (
+            VisualTransform::with_pivot(Vec2::new(0.0, crouch.bottom())),
+            Juice::default(),
+            AmbientTint::new(CHARACTER_AMBIENT_STRENGTH),
+            ParticleEmitter {
+                active: false,
+                ..ParticleEmitter::new(
+                    ParticleEffect {
+                        // kicked up behind the player
+                        direction: Vec2::new(-1.0, 0.6),
+                        spread: 0.4,
+                        ..ParticleEffect::dust()
+                    },
+                    RUNNING_DUST_RATE,
+                )
+                .with_offset(Vec2::new(-2.0, crouch.bottom()))
+            },
+            Afterimage {
+                active: false,
+                ..Afterimage::new(0.05, 0.25, Color::rgba(0.5, 0.7, 1.0, 0.6))
+            },
+        ),
+        crouch,
+        LedgeGrab::default(),
     ));
 }
 
@@ -68,15 +115,20 @@ pub fn move_player(
             as f32
             * (can_move as i8 as f32);
 
+        let move_speed = match player_state.is_crouching() {
+            true => player.crouch_speed,
+            false => player.move_speed,
+        };
+
         velocity.x = move_toward(
             velocity.x,
-            player.move_speed * direction,
+            move_speed * direction,
             player.acceleration,
             time.delta_seconds(),
         );
 
         let jump = keyboard_input.any_just_pressed([KeyCode::Up, KeyCode::Z]);
-        if jump && collision.get(&PlayerSensor::Down) && can_move {
+        if jump && collision.get(&PlayerSensor::Down) && can_move && !player_state.is_crouching() {
             velocity.y = player.jump_impulse;
         }
         // hit the ceiling
@@ -91,40 +143,38 @@ pub fn move_player(
 
 pub fn update_player_state(
     keyboard_input: Res<Input<KeyCode>>,
+    state_machine: Res<StateMachine<PlayerState, PlayerConditions>>,
     mut player_query: Query<(
         &crate::kinematics::Velocity,
         &Collision<PlayerSensor>,
         &mut PlayerState,
+        &mut Crouch,
         &Animation,
     )>,
 ) {
-    if let Ok((velocity, collision, mut player_state, player_animation)) =
+    if let Ok((velocity, collision, mut player_state, mut crouch, player_animation)) =
         player_query.get_single_mut()
     {
-        let new_state = match velocity.y > 0.0 {
-            true => PlayerState::Rising,
-            false => {
-                match collision.get(&PlayerSensor::Down) {
-                    true => {
-                        if player_state.eq(&PlayerState::Attack1) && !player_animation.finished {
-                            PlayerState::Attack1
-                        } else {
-                            if keyboard_input.just_pressed(KeyCode::Space) {
-                                // TODO : need to orient the character in the direction the player is
-                                // aiming to
-                                PlayerState::Attack1
-                            } else {
-                                match velocity.x == 0.0 {
-                                    true => PlayerState::Idle,
-                                    false => PlayerState::Run,
-                                }
-                            }
-                        }
-                    }
-                    false => PlayerState::Falling,
-                }
-            }
+        // ledge states are driven by grab_ledge
+        if player_state.is_on_ledge() {
+            return;
+        }
+
+        let conditions = PlayerConditions {
+            rising: velocity.y > 0.0,
+            grounded: collision.get(&PlayerSensor::Down),
+            moving: velocity.x != 0.0,
+            // standing back up is not possible while the head sensor is stuck under a ceiling
+            crouch_requested: keyboard_input.any_pressed([KeyCode::Down, KeyCode::S])
+                || (player_state.is_crouching() && collision.get(&PlayerSensor::Up)),
+            attack_pressed: keyboard_input.just_pressed(KeyCode::Space),
+            animation_finished: player_animation.is_finished(),
         };
+        let new_state = state_machine.next(*player_state, &conditions);
+
+        if crouch.crouching != new_state.is_crouching() {
+            crouch.crouching = new_state.is_crouching();
+        }
 
         player_state.set_if_neq(new_state);
     }
@@ -138,11 +188,12 @@ pub fn handle_player_collision_changes(
             Option<&mut Gravity>,
             &crate::kinematics::Velocity,
             &Collision<PlayerSensor>,
+            &PlayerState,
         ),
         Changed<Collision<PlayerSensor>>,
     >,
 ) {
-    for (entity, gravity_marker, velocity, collisions) in player_query.iter_mut() {
+    for (entity, gravity_marker, velocity, collisions, player_state) in player_query.iter_mut() {
         match gravity_marker {
             Some(_) => {
                 if collisions.get(&PlayerSensor::Down) && velocity.y <= 0.0 {
@@ -150,8 +201,87 @@ pub fn handle_player_collision_changes(
                 }
             }
             None => {
-                if !collisions.get(&PlayerSensor::Down) {
+                if !collisions.get(&PlayerSensor::Down) && !player_state.is_on_ledge() {
+                    commands.entity(entity).insert(Gravity);
+                }
+            }
+        }
+    }
+}
+
+pub fn grab_ledge(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    tile_grid: Res<TileGrid>,
+    mut player_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut crate::kinematics::Velocity,
+        &mut PlayerState,
+        &mut LedgeGrab,
+        &Orientation,
+        &Collision<PlayerSensor>,
+        &Animation,
+    )>,
+) {
+    for (
+        entity,
+        mut transform,
+        mut velocity,
+        mut player_state,
+        mut ledge_grab,
+        orientation,
+        collision,
+        animation,
+    ) in player_query.iter_mut()
+    {
+        let direction = match orientation {
+            Orientation::Right => 1.0,
+            Orientation::Left => -1.0,
+        };
+
+        match *player_state {
+            PlayerState::Hanging => {
+                if keyboard_input.any_just_pressed([KeyCode::Up, KeyCode::Z]) {
+                    *player_state = PlayerState::Climbing;
+                } else if keyboard_input.any_just_pressed([KeyCode::Down, KeyCode::S]) {
+                    ledge_grab.ledge = None;
+                    ledge_grab.cooldown = Timer::from_seconds(LEDGE_COOLDOWN, TimerMode::Once);
                     commands.entity(entity).insert(Gravity);
+                    *player_state = PlayerState::Falling;
+                }
+            }
+            PlayerState::Climbing => {
+                if animation.is_finished() {
+                    if let Some(ledge) = ledge_grab.ledge.take() {
+                        transform.translation.x = ledge.x + direction * CLIMB_OFFSET.x;
+                        transform.translation.y = ledge.y + CLIMB_OFFSET.y;
+                    }
+                    commands.entity(entity).insert(Gravity);
+                    *player_state = PlayerState::Idle;
+                }
+            }
+            _ => {
+                ledge_grab.cooldown.tick(time.delta());
+
+                let falling = velocity.y < 0.0 && !collision.get(&PlayerSensor::Down);
+                let wall_edge_in_reach = collision.get(&PlayerSensor::LedgeFront)
+                    && !collision.get(&PlayerSensor::LedgeAbove);
+                if !falling || !wall_edge_in_reach || !ledge_grab.cooldown.finished() {
+                    continue;
+                }
+
+                let hand = transform.translation.truncate()
+                    + Vec2::new(direction * HAND_OFFSET.x, HAND_OFFSET.y);
+                if let Some(ledge) = tile_grid.find_ledge(hand, direction, LEDGE_REACH) {
+                    transform.translation.x = ledge.x - direction * HAND_OFFSET.x;
+                    transform.translation.y = ledge.y - HAND_OFFSET.y;
+                    velocity.x = 0.0;
+                    velocity.y = 0.0;
+                    ledge_grab.ledge = Some(ledge);
+                    commands.entity(entity).remove::<Gravity>();
+                    *player_state = PlayerState::Hanging;
                 }
             }
         }
@@ -168,3 +298,47 @@ pub fn follow_player(
     camera_transform.translation.x = player_transform.translation.x;
     camera_transform.translation.y = player_transform.translation.y + 25.0;
 }
+
+/// Matches the walk cycles to the actual speed so the feet do not slide while accelerating.
+pub fn scale_walk_animation(
+    mut player_query: Query<
+        (
+            &crate::kinematics::Velocity,
+            &Player,
+            &PlayerState,
+            &mut Animation,
+        ),
+        Changed<crate::kinematics::Velocity>,
+    >,
+) {
+    for (velocity, player, player_state, mut animation) in player_query.iter_mut() {
+        let speed = match player_state {
+            PlayerState::Run => velocity.x.abs() / player.move_speed,
+            PlayerState::CrouchWalk => velocity.x.abs() / player.crouch_speed,
+            _ => 1.0,
+        };
+        animation.speed = speed.max(MIN_WALK_ANIMATION_SPEED);
+    }
+}
+
+pub fn emit_running_dust(
+    mut player_query: Query<(&PlayerState, &Collision<PlayerSensor>, &mut ParticleEmitter)>,
+) {
+    for (player_state, collision, mut emitter) in player_query.iter_mut() {
+        let running = *player_state == PlayerState::Run && collision.get(&PlayerSensor::Down);
+        if emitter.active != running {
+            emitter.active = running;
+        }
+    }
+}
+
+pub fn trail_fast_falls(
+    mut player_query: Query<(&crate::kinematics::Velocity, &mut Afterimage), With<Player>>,
+) {
+    for (velocity, mut afterimage) in player_query.iter_mut() {
+        let falling_fast = velocity.y < -FAST_FALL_SPEED;
+        if afterimage.active != falling_fast {
+            afterimage.active = falling_fast;
+        }
+    }
+}
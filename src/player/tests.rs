@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::{animation::Animation, test_support::TestGame};
+
+use super::{components::Player, state::PlayerState};
+
+// the player spawns at (82, -70), right above the first row
+#[rustfmt::skip]
+const LEVEL: &[&str] = &[
+    "         ########",
+    "#        #",
+    "##########",
+];
+const LEVEL_ORIGIN: Vec2 = Vec2::new(-80.0, -96.0);
+// floor top plus the half height of the player capsule
+const STANDING_Y: f32 = -96.0 + 14.0;
+
+fn game() -> TestGame {
+    TestGame::new().with_level(LEVEL_ORIGIN, LEVEL)
+}
+
+#[test]
+fn player_lands_on_the_floor() {
+    let mut game = game();
+    game.run(120);
+
+    assert_eq!(game.single::<PlayerState, Player>(), &PlayerState::Idle);
+    let transform = game.single::<Transform, Player>();
+    assert!((transform.translation.y - STANDING_Y).abs() < 1.0);
+}
+
+#[test]
+fn player_runs_while_key_is_held() {
+    let mut game = game();
+    game.run(60);
+    let start_x = game.single::<Transform, Player>().translation.x;
+
+    game.hold(KeyCode::Right, 60, 30).run(30);
+    assert_eq!(game.single::<PlayerState, Player>(), &PlayerState::Run);
+
+    game.run(60);
+    assert_eq!(game.single::<PlayerState, Player>(), &PlayerState::Idle);
+    let transform = game.single::<Transform, Player>();
+    assert!(transform.translation.x > start_x + 20.0);
+    assert!((transform.translation.y - STANDING_Y).abs() < 1.0);
+}
+
+#[test]
+fn run_cycle_follows_the_running_speed() {
+    let mut game = game();
+    game.run(60);
+
+    game.hold(KeyCode::Right, 60, 60).run(3);
+    let starting_speed = game.single::<Animation, Player>().speed;
+    assert!(starting_speed < 0.5);
+
+    game.run(30);
+    assert!((game.single::<Animation, Player>().speed - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn player_jumps_from_the_floor() {
+    let mut game = game();
+    game.run(60);
+
+    game.hold(KeyCode::Up, 60, 1).run(5);
+    assert_eq!(game.single::<PlayerState, Player>(), &PlayerState::Rising);
+    assert!(game.single::<Transform, Player>().translation.y > STANDING_Y + 1.0);
+
+    game.run(120);
+    assert_eq!(game.single::<PlayerState, Player>(), &PlayerState::Idle);
+}
+
+#[test]
+fn scripted_runs_are_deterministic() {
+    let mut positions = (0..2).map(|_| {
+        let mut game = game();
+        game.hold(KeyCode::Right, 30, 40)
+            .hold(KeyCode::Up, 50, 1)
+            .run(150);
+        game.single::<Transform, Player>().translation
+    });
+
+    assert_eq!(positions.next(), positions.next());
+}
+
+#[test]
+fn player_falling_past_a_wall_grabs_its_ledge() {
+    // a single column with its top edge 10 pixels below the player and its face right in front
+    let mut game = TestGame::new().with_level(Vec2::new(86.5, -80.0), &["#", "#", "#"]);
+    game.run(60);
+
+    assert_eq!(game.single::<PlayerState, Player>(), &PlayerState::Hanging);
+    // hands on the corner of the ledge
+    let transform = game.single::<Transform, Player>();
+    assert!((transform.translation.y - (-80.0 - 10.0)).abs() < 1.0);
+}
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::collision::CRATE_GROUP;
+
+const CRATE_SIZE: f32 = 16.0;
+const CRATE_COLOR: Color = Color::rgb(0.55, 0.35, 0.2);
+
+/// Turns `Pushable` entities into crates, independently of where they come from.
+pub struct PushablePlugin;
+
+impl Plugin for PushablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(add_crate_body);
+    }
+}
+
+#[derive(Component, Default)]
+pub struct Pushable;
+
+/// A crate placed in the level, or spawned at `position` with [`CrateBundle::new`]. LDtk
+/// overwrites the spatial bundle with the position of the entity in the level.
+#[derive(Bundle, LdtkEntity, Default)]
+pub struct CrateBundle {
+    pushable: Pushable,
+    spatial_bundle: SpatialBundle,
+}
+
+impl CrateBundle {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            pushable: Pushable,
+            spatial_bundle: SpatialBundle::from_transform(Transform::from_translation(
+                position.extend(0.0),
+            )),
+        }
+    }
+}
+
+#[derive(Bundle)]
+struct CrateBodyBundle {
+    name: Name,
+    rigid_body: RigidBody,
+    collider: Collider,
+    collision_groups: CollisionGroups,
+    mass_properties: ColliderMassProperties,
+    friction: Friction,
+    damping: Damping,
+    locked_axes: LockedAxes,
+    sprite: Sprite,
+    texture: Handle<Image>,
+}
+
+impl Default for CrateBodyBundle {
+    fn default() -> Self {
+        Self {
+            name: Name::new("Crate"),
+            rigid_body: RigidBody::Dynamic,
+            collider: Collider::cuboid(CRATE_SIZE / 2.0, CRATE_SIZE / 2.0),
+            collision_groups: CRATE_GROUP,
+            mass_properties: ColliderMassProperties::Mass(1.0),
+            friction: Friction::coefficient(0.8),
+            // without damping a single push would send a crate sliding across the level
+            damping: Damping {
+                linear_damping: 4.0,
+                angular_damping: 0.0,
+            },
+            // keeps stacks stable, crates slide and fall but never tip over
+            locked_axes: LockedAxes::ROTATION_LOCKED,
+            sprite: Sprite {
+                color: CRATE_COLOR,
+                custom_size: Some(Vec2::splat(CRATE_SIZE)),
+                ..default()
+            },
+            texture: Handle::default(),
+        }
+    }
+}
+
+fn add_crate_body(mut commands: Commands, crate_query: Query<Entity, Added<Pushable>>) {
+    for entity in crate_query.iter() {
+        commands.entity(entity).insert(CrateBodyBundle::default());
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use crate::{player::Player, test_support::TestGame};
+
+use super::{CrateBundle, Pushable};
+
+#[rustfmt::skip]
+const LEVEL: &[&str] = &[
+    "         ########",
+    "#        #",
+    "##########",
+];
+const LEVEL_ORIGIN: Vec2 = Vec2::new(-80.0, -96.0);
+// floor top plus the half height of a crate
+const RESTING_Y: f32 = -128.0 + 8.0;
+// same on the raised floor the player spawns above
+const RAISED_RESTING_Y: f32 = -96.0 + 8.0;
+// half height of the player capsule
+const PLAYER_HALF_HEIGHT: f32 = 14.0;
+
+#[test]
+fn crates_land_on_the_level() {
+    let mut game = TestGame::new().with_level(LEVEL_ORIGIN, LEVEL);
+    game.world().spawn(CrateBundle::new(Vec2::new(40.0, -90.0)));
+    game.run(120);
+
+    let transform = game.single::<Transform, Pushable>();
+    assert!((transform.translation.y - RESTING_Y).abs() < 1.0);
+}
+
+#[test]
+fn walking_into_a_crate_pushes_it() {
+    let mut game = TestGame::new().with_level(LEVEL_ORIGIN, LEVEL);
+    let start_x = 110.0;
+    game.world()
+        .spawn(CrateBundle::new(Vec2::new(start_x, RAISED_RESTING_Y)));
+    game.run(60);
+
+    game.hold(KeyCode::Right, 60, 60).run(90);
+
+    let transform = game.single::<Transform, Pushable>();
+    assert!(transform.translation.x > start_x + 10.0);
+    assert!((transform.translation.y - RAISED_RESTING_Y).abs() < 1.0);
+}
+
+#[test]
+fn crates_stack_and_can_be_stood_on() {
+    let mut game = TestGame::new().with_level(LEVEL_ORIGIN, LEVEL);
+    let bottom = game
+        .world()
+        .spawn(CrateBundle::new(Vec2::new(150.0, RAISED_RESTING_Y)))
+        .id();
+    let top = game
+        .world()
+        .spawn(CrateBundle::new(Vec2::new(150.0, RAISED_RESTING_Y + 16.0)))
+        .id();
+    // drop the player on top of the stack
+    game.run(1);
+    let world = game.world();
+    world
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(world)
+        .translation = Vec3::new(150.0, -20.0, 0.0);
+    game.run(120);
+
+    let world = game.world();
+    let bottom_y = world.get::<Transform>(bottom).unwrap().translation.y;
+    let top_y = world.get::<Transform>(top).unwrap().translation.y;
+    assert!((bottom_y - RAISED_RESTING_Y).abs() < 1.0);
+    assert!((top_y - (RAISED_RESTING_Y + 16.0)).abs() < 1.0);
+    let player_y = game.single::<Transform, Player>().translation.y;
+    assert!((player_y - (top_y + 8.0 + PLAYER_HALF_HEIGHT)).abs() < 1.0);
+}
@@ -0,0 +1,245 @@
+use std::mem::discriminant;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{collision::Hit, GameSet};
+
+#[cfg(test)]
+mod tests;
+
+// sprite colors multiply the texture, going far above 1 saturates every channel to white
+const FLASH_COLOR: Color = Color::rgb(8.0, 8.0, 8.0);
+const HIT_FLASH_DURATION: f32 = 0.15;
+// a hit target blinks while it recovers from the hit
+const HIT_BLINK_DURATION: f32 = 0.6;
+const HIT_BLINK_INTERVAL: f32 = 0.05;
+
+pub struct SpriteEffectsPlugin;
+
+impl Plugin for SpriteEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpriteEffectRequest>()
+            .add_system(flash_hit_targets.in_set(GameSet::Update))
+            .add_system(
+                request_sprite_effects
+                    .in_set(GameSet::AfterUpdate)
+                    .after(flash_hit_targets),
+            )
+            .add_system(apply_sprite_effects.in_set(GameSet::Render));
+    }
+}
+
+/// How the strength of an effect evolves over its duration, from full to nothing.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Full strength until the effect ends.
+    Hold,
+}
+
+impl Easing {
+    /// Strength of the effect once `progress` of its duration has elapsed.
+    pub fn strength(&self, progress: f32) -> f32 {
+        let remaining = 1.0 - progress.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => remaining,
+            Easing::EaseIn => remaining * remaining,
+            Easing::EaseOut => 1.0 - progress * progress,
+            Easing::EaseInOut => remaining * remaining * (3.0 - 2.0 * remaining),
+            Easing::Hold => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpriteEffectKind {
+    /// Pushes the sprite toward white.
+    Flash,
+    /// Hides the sprite every other `interval` seconds.
+    Blink { interval: f32 },
+    /// Blends the sprite color toward `color`, by its alpha.
+    Tint { color: Color },
+}
+
+#[derive(Debug, Clone)]
+pub struct SpriteEffect {
+    pub kind: SpriteEffectKind,
+    pub easing: Easing,
+    timer: Timer,
+}
+
+impl SpriteEffect {
+    pub fn new(kind: SpriteEffectKind, duration: f32) -> Self {
+        Self {
+            kind,
+            easing: Easing::default(),
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        }
+    }
+
+    pub fn flash(duration: f32) -> Self {
+        Self::new(SpriteEffectKind::Flash, duration)
+    }
+
+    pub fn blink(duration: f32, interval: f32) -> Self {
+        Self::new(SpriteEffectKind::Blink { interval }, duration).with_easing(Easing::Hold)
+    }
+
+    pub fn tint(color: Color, duration: f32) -> Self {
+        Self::new(SpriteEffectKind::Tint { color }, duration)
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    fn strength(&self) -> f32 {
+        self.easing.strength(self.timer.percent())
+    }
+}
+
+/// Effects currently shown on a sprite, on top of its `base_color`.
+///
+/// An effect replaces the running one of the same kind, a new tint replaces the old tint whatever
+/// its color. Effects of different kinds combine: the tint applies first, then the flash, then the
+/// blink hides the result.
+#[derive(Component, Debug, Clone)]
+pub struct SpriteEffects {
+    pub base_color: Color,
+    effects: Vec<SpriteEffect>,
+}
+
+impl Default for SpriteEffects {
+    fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
+impl SpriteEffects {
+    pub fn new(base_color: Color) -> Self {
+        Self {
+            base_color,
+            effects: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, effect: SpriteEffect) {
+        self.effects
+            .retain(|running| discriminant(&running.kind) != discriminant(&effect.kind));
+        self.effects.push(effect);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Advances every effect, dropping the ones that ended.
+    pub fn tick(&mut self, delta: std::time::Duration) {
+        for effect in self.effects.iter_mut() {
+            effect.timer.tick(delta);
+        }
+        self.effects.retain(|effect| !effect.timer.finished());
+    }
+
+    /// Color of the sprite with every running effect applied.
+    pub fn color(&self) -> Color {
+        let find = |matches: fn(&SpriteEffectKind) -> bool| {
+            self.effects.iter().find(|effect| matches(&effect.kind))
+        };
+        let mut color = self.base_color;
+
+        if let Some(effect) = find(|kind| matches!(kind, SpriteEffectKind::Tint { .. })) {
+            if let SpriteEffectKind::Tint { color: tint } = effect.kind {
+                color = lerp_rgb(color, tint, tint.a() * effect.strength());
+            }
+        }
+        if let Some(effect) = find(|kind| matches!(kind, SpriteEffectKind::Flash)) {
+            color = lerp_rgb(color, FLASH_COLOR, effect.strength());
+        }
+        if let Some(effect) = find(|kind| matches!(kind, SpriteEffectKind::Blink { .. })) {
+            if let SpriteEffectKind::Blink { interval } = effect.kind {
+                let hidden = (effect.timer.elapsed_secs() / interval) as u32 % 2 == 1;
+                if hidden && effect.strength() > 0.0 {
+                    color.set_a(0.0);
+                }
+            }
+        }
+
+        color
+    }
+}
+
+/// Asks for an effect on a sprite, adding [`SpriteEffects`] to it when needed.
+pub struct SpriteEffectRequest {
+    pub entity: Entity,
+    pub effect: SpriteEffect,
+}
+
+fn lerp_rgb(from: Color, to: Color, t: f32) -> Color {
+    let [r, g, b, a] = from.as_rgba_f32();
+    let [to_r, to_g, to_b, _] = to.as_rgba_f32();
+    Color::rgba(
+        r + (to_r - r) * t,
+        g + (to_g - g) * t,
+        b + (to_b - b) * t,
+        a,
+    )
+}
+
+fn flash_hit_targets(mut hits: EventReader<Hit>, mut requests: EventWriter<SpriteEffectRequest>) {
+    for hit in hits.iter() {
+        requests.send(SpriteEffectRequest {
+            entity: hit.target,
+            effect: SpriteEffect::flash(HIT_FLASH_DURATION).with_easing(Easing::EaseIn),
+        });
+        requests.send(SpriteEffectRequest {
+            entity: hit.target,
+            effect: SpriteEffect::blink(HIT_BLINK_DURATION, HIT_BLINK_INTERVAL),
+        });
+    }
+}
+
+fn request_sprite_effects(
+    mut commands: Commands,
+    mut requests: EventReader<SpriteEffectRequest>,
+    mut sprite_query: Query<(&TextureAtlasSprite, Option<&mut SpriteEffects>)>,
+) {
+    // several requests can target a sprite that does not have its effects yet
+    let mut added: HashMap<Entity, SpriteEffects> = HashMap::new();
+    for request in requests.iter() {
+        let Ok((sprite, effects)) = sprite_query.get_mut(request.entity) else {
+            continue;
+        };
+        match effects {
+            Some(mut effects) => effects.add(request.effect.clone()),
+            None => added
+                .entry(request.entity)
+                // the color of the sprite before any effect is what it goes back to
+                .or_insert_with(|| SpriteEffects::new(sprite.color))
+                .add(request.effect.clone()),
+        }
+    }
+
+    for (entity, effects) in added {
+        commands.entity(entity).insert(effects);
+    }
+}
+
+pub fn apply_sprite_effects(
+    time: Res<Time>,
+    mut sprite_query: Query<(&mut SpriteEffects, &mut TextureAtlasSprite)>,
+) {
+    for (mut effects, mut sprite) in sprite_query.iter_mut() {
+        // finished effects leave the base color behind, then the sprite is left alone
+        if effects.is_empty() {
+            continue;
+        }
+        effects.tick(time.delta());
+        sprite.color = effects.color();
+    }
+}
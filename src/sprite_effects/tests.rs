@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{Easing, SpriteEffect, SpriteEffects, FLASH_COLOR};
+
+#[test]
+fn easings_fade_from_full_to_nothing() {
+    for easing in [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ] {
+        assert_eq!(easing.strength(0.0), 1.0);
+        assert_eq!(easing.strength(1.0), 0.0);
+    }
+    assert_eq!(Easing::Hold.strength(0.5), 1.0);
+}
+
+#[test]
+fn effects_go_back_to_the_base_color() {
+    let base_color = Color::rgb(0.5, 0.25, 1.0);
+    let mut effects = SpriteEffects::new(base_color);
+    effects.add(SpriteEffect::flash(0.5).with_easing(Easing::Hold));
+    assert_eq!(effects.color(), FLASH_COLOR);
+
+    effects.tick(Duration::from_secs_f32(0.5));
+    assert!(effects.is_empty());
+    assert_eq!(effects.color(), base_color);
+}
+
+#[test]
+fn effects_of_the_same_kind_replace_each_other() {
+    let mut effects = SpriteEffects::default();
+    effects.add(SpriteEffect::tint(Color::RED, 1.0).with_easing(Easing::Hold));
+    effects.add(SpriteEffect::tint(Color::GREEN, 1.0).with_easing(Easing::Hold));
+    assert_eq!(effects.color(), Color::GREEN);
+}
+
+#[test]
+fn blink_hides_every_other_interval_over_the_flash() {
+    let mut effects = SpriteEffects::default();
+    effects.add(SpriteEffect::flash(1.0).with_easing(Easing::Hold));
+    effects.add(SpriteEffect::blink(1.0, 0.25));
+    assert_eq!(effects.color().a(), 1.0);
+
+    effects.tick(Duration::from_secs_f32(0.25));
+    assert_eq!(effects.color().a(), 0.0);
+    assert_eq!(effects.color().r(), FLASH_COLOR.r());
+
+    effects.tick(Duration::from_secs_f32(0.25));
+    assert_eq!(effects.color().a(), 1.0);
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Condition on the facts `C` gathered by a character each frame.
+pub type Guard<C> = fn(&C) -> bool;
+
+enum Source<S> {
+    Any,
+    States(Vec<S>),
+}
+
+struct StateTransition<S, C> {
+    source: Source<S>,
+    target: S,
+    guard: Guard<C>,
+    interrupt: bool,
+}
+
+/// Graph of guarded transitions between the states `S` of a character.
+///
+/// Transitions are tried in declaration order, the first one whose guard holds wins, so the most
+/// important ones are declared first. A locked state can only be left through an interrupt.
+#[derive(Resource)]
+pub struct StateMachine<S, C> {
+    transitions: Vec<StateTransition<S, C>>,
+    locks: Vec<(S, Guard<C>)>,
+}
+
+impl<S: Copy + PartialEq, C> Default for StateMachine<S, C> {
+    fn default() -> Self {
+        Self {
+            transitions: Vec::new(),
+            locks: Vec::new(),
+        }
+    }
+}
+
+impl<S: Copy + PartialEq, C> StateMachine<S, C> {
+    /// Goes to `target` from any state when `guard` holds, even out of a locked state.
+    pub fn interrupt(mut self, target: S, guard: Guard<C>) -> Self {
+        self.transitions.push(StateTransition {
+            source: Source::Any,
+            target,
+            guard,
+            interrupt: true,
+        });
+        self
+    }
+
+    /// Goes to `target` from any state when `guard` holds.
+    pub fn from_any(mut self, target: S, guard: Guard<C>) -> Self {
+        self.transitions.push(StateTransition {
+            source: Source::Any,
+            target,
+            guard,
+            interrupt: false,
+        });
+        self
+    }
+
+    /// Goes to `target` from one of `sources` when `guard` holds.
+    pub fn transition(mut self, sources: &[S], target: S, guard: Guard<C>) -> Self {
+        self.transitions.push(StateTransition {
+            source: Source::States(sources.to_vec()),
+            target,
+            guard,
+            interrupt: false,
+        });
+        self
+    }
+
+    /// Keeps the machine in `state` while `guard` holds, unless an interrupt fires.
+    pub fn lock(mut self, state: S, guard: Guard<C>) -> Self {
+        self.locks.push((state, guard));
+        self
+    }
+
+    /// State to be in given the `conditions` of this frame, `current` when no transition fires.
+    pub fn next(&self, current: S, conditions: &C) -> S {
+        let locked = self
+            .locks
+            .iter()
+            .any(|(state, guard)| *state == current && guard(conditions));
+
+        self.transitions
+            .iter()
+            .filter(|transition| transition.interrupt || !locked)
+            .filter(|transition| match &transition.source {
+                Source::Any => true,
+                Source::States(states) => states.contains(&current),
+            })
+            .find(|transition| (transition.guard)(conditions))
+            .map_or(current, |transition| transition.target)
+    }
+}
@@ -0,0 +1,65 @@
+use super::StateMachine;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    Walk,
+    Attack,
+    Fall,
+}
+
+#[derive(Default)]
+struct Conditions {
+    grounded: bool,
+    moving: bool,
+    attacking: bool,
+    animation_finished: bool,
+}
+
+fn machine() -> StateMachine<State, Conditions> {
+    StateMachine::default()
+        .interrupt(State::Fall, |c| !c.grounded)
+        .lock(State::Attack, |c| !c.animation_finished)
+        .transition(&[State::Idle, State::Walk], State::Attack, |c| c.attacking)
+        .from_any(State::Walk, |c| c.moving)
+        .from_any(State::Idle, |_| true)
+}
+
+#[test]
+fn first_transition_to_hold_wins() {
+    let conditions = Conditions {
+        grounded: true,
+        moving: true,
+        attacking: true,
+        ..Default::default()
+    };
+    assert_eq!(machine().next(State::Idle, &conditions), State::Attack);
+}
+
+#[test]
+fn transitions_only_leave_their_sources() {
+    let conditions = Conditions {
+        grounded: true,
+        attacking: true,
+        animation_finished: true,
+        ..Default::default()
+    };
+    assert_eq!(machine().next(State::Fall, &conditions), State::Idle);
+}
+
+#[test]
+fn locked_states_are_only_left_by_interrupts() {
+    let mut conditions = Conditions {
+        grounded: true,
+        moving: true,
+        ..Default::default()
+    };
+    assert_eq!(machine().next(State::Attack, &conditions), State::Attack);
+
+    conditions.grounded = false;
+    assert_eq!(machine().next(State::Attack, &conditions), State::Fall);
+
+    conditions.grounded = true;
+    conditions.animation_finished = true;
+    assert_eq!(machine().next(State::Attack, &conditions), State::Walk);
+}
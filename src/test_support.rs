@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::HashMap};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    animation::animate,
+    kinematics::KinematicsPlugin,
+    knight::KnightPlugin,
+    map::{Wall, WallPlugin},
+    physics::PhysicsExtensionPlugin,
+    player::PlayerPlugin,
+    pushable::PushablePlugin,
+    GameSet, GameSetsPlugin,
+};
+
+pub const FRAME: Duration = Duration::from_nanos(16_666_667);
+const TILE_SIZE: f32 = 16.0;
+
+enum KeyAction {
+    Press(KeyCode),
+    Release(KeyCode),
+}
+
+/// Runs the gameplay plugins without a window or a renderer, one fixed frame at a time, with
+/// keyboard input replayed from a script.
+pub struct TestGame {
+    app: App,
+    frame: u32,
+    elapsed: Duration,
+    script: HashMap<u32, Vec<KeyAction>>,
+}
+
+impl TestGame {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .init_resource::<Input<KeyCode>>()
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+            .insert_resource(RapierConfiguration {
+                timestep_mode: TimestepMode::Fixed {
+                    dt: FRAME.as_secs_f32(),
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .add_plugin(PhysicsExtensionPlugin)
+            .add_plugin(WallPlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(KinematicsPlugin)
+            .add_plugin(KnightPlugin)
+            .add_plugin(PushablePlugin)
+            .add_plugin(GameSetsPlugin)
+            .add_system(animate.in_set(GameSet::Render));
+
+        // follow_player expects a camera to move around
+        app.world
+            .spawn((Camera2d::default(), TransformBundle::default()));
+
+        Self {
+            app,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            script: HashMap::new(),
+        }
+    }
+
+    /// Spawns a wall for every `#` in `rows`, the first row being the top one and `origin` the
+    /// top left corner of the first cell.
+    pub fn with_level(mut self, origin: Vec2, rows: &[&str]) -> Self {
+        for (row, line) in rows.iter().enumerate() {
+            for (column, cell) in line.chars().enumerate() {
+                if cell != '#' {
+                    continue;
+                }
+                let center = origin
+                    + Vec2::new(
+                        column as f32 * TILE_SIZE + TILE_SIZE / 2.0,
+                        -(row as f32) * TILE_SIZE - TILE_SIZE / 2.0,
+                    );
+                self.app.world.spawn((
+                    Name::new("Wall"),
+                    Wall,
+                    TransformBundle::from_transform(Transform::from_translation(
+                        center.extend(0.0),
+                    )),
+                ));
+            }
+        }
+        self
+    }
+
+    /// Holds `key` down for `frames` frames starting at frame `from`.
+    pub fn hold(&mut self, key: KeyCode, from: u32, frames: u32) -> &mut Self {
+        self.script
+            .entry(from)
+            .or_default()
+            .push(KeyAction::Press(key));
+        self.script
+            .entry(from + frames)
+            .or_default()
+            .push(KeyAction::Release(key));
+        self
+    }
+
+    pub fn run(&mut self, frames: u32) -> &mut Self {
+        for _ in 0..frames {
+            self.step();
+        }
+        self
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// The single `C` component carried by the entity marked with `M`.
+    pub fn single<C: Component, M: Component>(&mut self) -> &C {
+        let mut query = self.app.world.query_filtered::<&C, With<M>>();
+        query.single(&self.app.world)
+    }
+
+    fn step(&mut self) {
+        let mut input = self.app.world.resource_mut::<Input<KeyCode>>();
+        input.clear();
+        for action in self.script.remove(&self.frame).unwrap_or_default() {
+            match action {
+                KeyAction::Press(key) => input.press(key),
+                KeyAction::Release(key) => input.release(key),
+            }
+        }
+
+        // time is stepped by hand so every run sees exactly the same deltas
+        self.elapsed += FRAME;
+        let startup = self.app.world.resource::<Time>().startup();
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualInstant(startup + self.elapsed));
+
+        self.app.update();
+        self.frame += 1;
+    }
+}